@@ -0,0 +1,92 @@
+use axum::extract::{ Json, State };
+use axum::Router;
+use axum::routing::post;
+
+use sea_orm::entity::prelude::*;
+use serde::{ Deserialize, Serialize };
+use utoipa::{ OpenApi, ToSchema };
+
+use crate::api::logs::LogManager;
+use crate::app::AppState;
+use crate::common::{ jwt, password };
+use crate::common::response::ApiResponse;
+use crate::common::result::{ ApiError, ApiResult };
+use crate::entity::prelude::Users;
+use crate::entity::users;
+use crate::t;
+
+// 登录时的请求体
+#[derive(Deserialize, ToSchema)]
+pub struct LoginRequest {
+    name: String,
+    password: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct LoginResponse {
+    access_token: String,
+    token_type: &'static str,
+    expires_in: u64,
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/auth/login",
+    tag = "auth",
+    security(()),
+    request_body = LoginRequest,
+    responses(
+        (status = 200, description = "登录成功，返回访问令牌", body = ApiResponse<LoginResponse>),
+        (status = 401, description = "用户名或密码错误")
+    )
+)]
+async fn login(
+    State(AppState { db, hub, config, .. }): State<AppState>,
+    Json(data): Json<LoginRequest>
+) -> ApiResult<ApiResponse<LoginResponse>> {
+    let user = Users::find().filter(users::Column::Name.eq(&data.name)).one(&db).await?;
+
+    // 用户不存在时同样计算一次 bcrypt，避免通过响应时间判断用户名是否存在
+    let verified = match &user {
+        Some(user) => password::verify(&data.password, &user.password).await,
+        None => {
+            password::verify(&data.password, password::DUMMY_HASH).await;
+            false
+        }
+    };
+    let Some(user) = user.filter(|_| verified) else {
+        LogManager::create_log(
+            &db,
+            &hub,
+            LogManager::warning(),
+            format!("用户<{}>登录失败", data.name)
+        ).await?;
+        return Err(ApiError::Unauthorized(t!("auth.invalid_credentials")));
+    };
+
+    let access_token = jwt
+        ::encode_token(&config.jwt, user.user_id.clone(), user.role.clone())
+        .map_err(|e| ApiError::Internal(e.into()))?;
+
+    LogManager::create_log(&db, &hub, LogManager::info(), format!("用户<{}>登录成功", user.name)).await?;
+
+    Ok(
+        ApiResponse::ok(
+            t!("auth.login_success"),
+            Some(LoginResponse {
+                access_token,
+                token_type: "Bearer",
+                expires_in: config.jwt.expires_in,
+            })
+        )
+    )
+}
+
+#[derive(OpenApi)]
+#[openapi(paths(login), tags((name = "auth", description = "登录认证")))]
+pub struct AuthApi;
+
+pub fn create_auth_router() -> Router<AppState> {
+    Router::new().route("/login", post(login))
+}
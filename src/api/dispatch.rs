@@ -0,0 +1,231 @@
+use axum::extract::State;
+use axum::Router;
+use axum::routing::post;
+use sea_orm::entity::prelude::*;
+use sea_orm::{ ActiveValue, IntoActiveModel, QueryFilter, QuerySelect, TransactionTrait };
+use serde::{ Deserialize, Serialize };
+use utoipa::{ OpenApi, ToSchema };
+use validator::Validate;
+
+use crate::api::geofence;
+use crate::api::incident::record_status_change;
+use crate::api::logs::LogManager;
+use crate::api::mission::{ ensure_drone_available, reserving_missions };
+use crate::app::AppState;
+use crate::common::geo;
+use crate::common::jwt::Claims;
+use crate::common::response::ApiResponse;
+use crate::common::result::{ ApiError, ApiResult };
+use crate::common::validation::{ ValidJson, ValidPath };
+use crate::entity::missions::ActiveModel as MissionsActiveModel;
+use crate::entity::prelude::{ Drones, Incidents, Missions };
+use crate::entity::sea_orm_active_enums::{ IncidentStatus, Status };
+use crate::entity::{ drones, incidents, missions };
+use crate::t;
+
+// 评分中电量与距离的权重
+const BATTERY_WEIGHT: f64 = 0.4;
+const DISTANCE_WEIGHT: f64 = 0.6;
+
+// 超过该距离（米）的无人机距离得分为 0
+const MAX_DISPATCH_DISTANCE: f64 = 50_000.0;
+
+// 电量低于该值的无人机不参与派遣
+pub(super) const MIN_DISPATCH_BATTERY: u8 = 20;
+
+#[derive(Debug, Deserialize, ToSchema, Validate)]
+#[serde(rename_all = "camelCase")]
+struct DispatchRequest {
+    /// 手动指定的无人机，不指定时选择排名第一的无人机
+    #[validate(length(min = 1, max = 32))]
+    drone_id: Option<String>,
+    /// 只返回候选排名，不创建任务
+    #[serde(default)]
+    dry_run: bool,
+}
+
+// 候选无人机及其评分，distance 为最近一次上报位置到告警的距离（米）
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+struct DispatchCandidate {
+    drone_id: String,
+    name: String,
+    battery: u8,
+    distance: Option<f64>,
+    score: f64,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+struct DispatchResponse {
+    dry_run: bool,
+    // 选中的无人机；dryRun 时为将会选中的无人机，没有候选时为空
+    drone_id: Option<String>,
+    candidates: Vec<DispatchCandidate>,
+    mission: Option<missions::Model>,
+}
+
+// 电量越高、距离越近得分越高；没有位置信息的无人机只计电量得分
+fn score(battery: u8, distance: Option<f64>) -> f64 {
+    let battery_score = f64::from(battery.min(100)) / 100.0;
+    let distance_score = distance.map_or(0.0, |distance| {
+        1.0 - (distance / MAX_DISPATCH_DISTANCE).min(1.0)
+    });
+    battery_score * BATTERY_WEIGHT + distance_score * DISTANCE_WEIGHT
+}
+
+// 被占用的无人机，自动派遣和按型号生成任务时跳过
+pub(super) async fn reserved_drones<C: ConnectionTrait>(conn: &C) -> ApiResult<Vec<String>> {
+    let missions = Missions::find().filter(reserving_missions()).all(conn).await?;
+    Ok(missions.into_iter().map(|mission| mission.drone_id).collect())
+}
+
+// 已激活、空闲、电量充足且未被占用的无人机，按得分从高到低排序
+async fn rank_candidates<C: ConnectionTrait>(
+    conn: &C,
+    incident: &incidents::Model
+) -> ApiResult<Vec<DispatchCandidate>> {
+    let busy = reserved_drones(conn).await?;
+
+    let drones = Drones::find()
+        .filter(drones::Column::Activate.eq(1))
+        .filter(drones::Column::Status.eq(Status::Idle))
+        .filter(drones::Column::Battery.gte(MIN_DISPATCH_BATTERY))
+        .filter(drones::Column::DroneId.is_not_in(busy))
+        .all(conn).await?;
+
+    let (lat, lng) = (geo::to_f64(&incident.lat), geo::to_f64(&incident.lng));
+    let mut candidates: Vec<DispatchCandidate> = drones
+        .into_iter()
+        .map(|drone| {
+            let distance = drone.last_known_lat
+                .zip(drone.last_known_lng)
+                .map(|(drone_lat, drone_lng)| {
+                    geo::haversine(lat, lng, geo::to_f64(&drone_lat), geo::to_f64(&drone_lng))
+                });
+            DispatchCandidate {
+                score: score(drone.battery, distance),
+                drone_id: drone.drone_id,
+                name: drone.name,
+                battery: drone.battery,
+                distance,
+            }
+        })
+        .collect();
+    candidates.sort_by(|a, b| b.score.total_cmp(&a.score));
+
+    Ok(candidates)
+}
+
+// 为未处理的告警派遣无人机：创建指向告警位置的任务，并将告警置为处理中
+#[utoipa::path(
+    post,
+    path = "/api/incidents/{id}/dispatch",
+    tag = "incidents",
+    params(("id" = String, Path, description = "告警 ID")),
+    request_body = DispatchRequest,
+    responses(
+        (status = 200, description = "派遣结果；dryRun 时只返回候选排名，不检查无人机占用和禁飞区", body = ApiResponse<DispatchResponse>),
+        (status = 409, description = "告警不是待处理状态、没有可用无人机或指定的无人机被占用（包括已派遣给其他告警尚未开始的任务），或告警位于禁飞区")
+    )
+)]
+async fn dispatch_incident(
+    claims: Claims,
+    State(AppState { db, hub, .. }): State<AppState>,
+    ValidPath(id): ValidPath<String>,
+    ValidJson(data): ValidJson<DispatchRequest>
+) -> ApiResult<ApiResponse<DispatchResponse>> {
+    let txn = db.begin().await?;
+
+    // 锁住告警行，避免并发派遣同一告警时都通过待处理状态检查；预览不加锁
+    let mut select = Incidents::find_by_id(&id);
+    if !data.dry_run {
+        select = select.lock_exclusive();
+    }
+    let Some(incident) = select.one(&txn).await? else {
+        return Err(ApiError::NotFound(t!("incident.not_found", id = id)));
+    };
+    if incident.status != IncidentStatus::Open {
+        return Err(
+            ApiError::Conflict(
+                t!("dispatch.not_open", id = id, status = format!("{:?}", incident.status))
+            )
+        );
+    }
+
+    let candidates = rank_candidates(&txn, &incident).await?;
+
+    // 预览只返回候选排名，不检查无人机是否可用，也不锁无人机
+    if data.dry_run {
+        let drone_id = data.drone_id.or_else(|| {
+            candidates.first().map(|candidate| candidate.drone_id.clone())
+        });
+        return Ok(
+            ApiResponse::ok(
+                t!("dispatch.preview"),
+                Some(DispatchResponse { dry_run: true, drone_id, candidates, mission: None })
+            )
+        );
+    }
+
+    let drone_id = match data.drone_id {
+        Some(drone_id) => drone_id,
+        None =>
+            candidates
+                .first()
+                .map(|candidate| candidate.drone_id.clone())
+                .ok_or_else(|| ApiError::Conflict(t!("dispatch.no_candidate")))?,
+    };
+
+    // 锁住选中的无人机后再确认没有被其他告警或模板占用，避免连续派遣选中同一架
+    ensure_drone_available(&txn, &drone_id, None).await?;
+
+    geofence::check_route(&txn, Some((incident.lat, incident.lng)), &[]).await?.ensure_clear()?;
+
+    let mission = MissionsActiveModel {
+        mission_id: ActiveValue::set(xid::new().to_string()),
+        user_id: ActiveValue::set(claims.user_id.clone()),
+        drone_id: ActiveValue::set(drone_id.clone()),
+        target_lat: ActiveValue::set(Some(incident.lat)),
+        target_lng: ActiveValue::set(Some(incident.lng)),
+        status: ActiveValue::set(Status::Idle),
+        incident_id: ActiveValue::set(Some(incident.incident_id.clone())),
+        ..Default::default()
+    };
+    let mission = mission.insert(&txn).await?;
+
+    let from = incident.status.clone();
+    let mut incident = incident.into_active_model();
+    incident.status = ActiveValue::set(IncidentStatus::Processing);
+    incident.update(&txn).await?;
+    record_status_change(&txn, &id, from, IncidentStatus::Processing, &claims.user_id).await?;
+
+    txn.commit().await?;
+
+    LogManager::create_log(
+        &db,
+        &hub,
+        LogManager::info(),
+        format!("用户<{}>为告警<{}>派遣无人机<{}>，任务<{}>", claims.user_id, id, drone_id, mission.mission_id)
+    ).await?;
+
+    Ok(
+        ApiResponse::ok(
+            t!("dispatch.dispatched", drone = drone_id),
+            Some(DispatchResponse {
+                dry_run: false,
+                drone_id: Some(drone_id),
+                candidates,
+                mission: Some(mission),
+            })
+        )
+    )
+}
+
+#[derive(OpenApi)]
+#[openapi(paths(dispatch_incident))]
+pub struct DispatchApi;
+
+pub fn create_dispatch_router() -> Router<AppState> {
+    Router::new().route("/{id}/dispatch", post(dispatch_incident))
+}
@@ -0,0 +1,113 @@
+use std::sync::LazyLock;
+
+use axum::Json;
+use axum::Router;
+use axum::extract::Path;
+use axum::http::header;
+use axum::response::{ IntoResponse, Redirect, Response };
+use axum::routing::get;
+use rust_embed::RustEmbed;
+use utoipa::openapi::security::{ HttpAuthScheme, HttpBuilder, SecurityScheme };
+use utoipa::{ Modify, OpenApi };
+
+use crate::app::AppState;
+use crate::common::response::ErrorResponse;
+use crate::common::result::{ ApiError, ErrorCode };
+
+use super::{
+    auth,
+    dispatch,
+    drone,
+    events,
+    geofence,
+    incident,
+    live,
+    logs,
+    maintenance,
+    mission,
+    telemetry,
+    template,
+    usage,
+    user,
+    waypoint,
+};
+use crate::t;
+
+// 登录后获得的访问令牌，通过 Authorization: Bearer <token> 携带
+struct BearerAuth;
+
+impl Modify for BearerAuth {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        let components = openapi.components.get_or_insert_with(Default::default);
+        components.add_security_scheme(
+            "bearer",
+            SecurityScheme::Http(
+                HttpBuilder::new().scheme(HttpAuthScheme::Bearer).bearer_format("JWT").build()
+            )
+        );
+    }
+}
+
+#[derive(OpenApi)]
+#[openapi(
+    info(
+        title = "UAV 调度平台 API",
+        description = "无人机、任务、事件与告警管理接口。失败时返回 ErrorResponse，code 与 HTTP 状态码一致"
+    ),
+    modifiers(&BearerAuth),
+    components(schemas(ErrorResponse, ErrorCode)),
+    security(("bearer" = []))
+)]
+struct ApiDoc;
+
+// 各模块的接口文档在首次访问时合并
+static OPENAPI: LazyLock<utoipa::openapi::OpenApi> = LazyLock::new(|| {
+    let mut doc = ApiDoc::openapi();
+    for api in [
+        auth::AuthApi::openapi(),
+        user::UserApi::openapi(),
+        drone::DroneApi::openapi(),
+        usage::UsageApi::openapi(),
+        maintenance::MaintenanceApi::openapi(),
+        mission::MissionApi::openapi(),
+        waypoint::WaypointApi::openapi(),
+        template::TemplateApi::openapi(),
+        geofence::GeofenceApi::openapi(),
+        logs::LogApi::openapi(),
+        events::EventApi::openapi(),
+        dispatch::DispatchApi::openapi(),
+        incident::IncidentApi::openapi(),
+        telemetry::TelemetryApi::openapi(),
+        live::LiveApi::openapi(),
+    ] {
+        doc.merge(api);
+    }
+    doc
+});
+
+// 打包进可执行文件的 Swagger UI 静态资源
+#[derive(RustEmbed)]
+#[folder = "assets/swagger-ui/"]
+struct SwaggerUi;
+
+async fn openapi_json() -> Json<&'static utoipa::openapi::OpenApi> {
+    Json(&OPENAPI)
+}
+
+async fn swagger_ui(path: Option<Path<String>>) -> Response {
+    let path = path.map(|Path(path)| path).unwrap_or_else(|| "index.html".to_string());
+
+    match SwaggerUi::get(&path) {
+        Some(file) =>
+            ([(header::CONTENT_TYPE, file.metadata.mimetype().to_string())], file.data).into_response(),
+        None => ApiError::NotFound(t!("docs.not_found")).into_response(),
+    }
+}
+
+pub fn create_docs_router() -> Router<AppState> {
+    Router::new()
+        .route("/openapi.json", get(openapi_json))
+        .route("/docs", get(async || Redirect::permanent("/api/docs/")))
+        .route("/docs/", get(swagger_ui))
+        .route("/docs/{*path}", get(swagger_ui))
+}
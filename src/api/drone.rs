@@ -1,4 +1,4 @@
-use axum::extract::{ Path, Query, State, Json };
+use axum::extract::State;
 use axum::Router;
 use axum::routing::{ delete, get, post, put };
 use sea_orm::{
@@ -12,25 +12,58 @@ use sea_orm::{
     QueryFilter,
     FromQueryResult,
     QuerySelect,
+    Order,
+    TransactionTrait,
 };
-use sea_orm::prelude::Expr;
+use sea_orm::prelude::{ DateTime, Expr };
 use crate::app::AppState;
-use crate::common::response::ApiResponse;
+use crate::common::auth::{ AdminOnly, Authorized };
+use crate::common::response::{ ApiResponse, MessageResponse };
 use crate::entity::drones::ActiveModel as DronesActiveModel;
-use crate::entity::drones;
-use crate::entity::prelude::Drones;
+use crate::entity::{ drones, mission_templates };
+use crate::entity::prelude::{ Drones, MissionTemplates };
 use crate::common::page::{ Page, PaginationParams };
+use crate::common::query::{ parse_enum, FieldKind, ListParams, ListQuery, QueryField, Queryable };
 use crate::common::result::{ ApiError, ApiResult };
+use crate::common::validation::{ ValidJson, ValidPath, ValidQuery };
 use crate::entity::sea_orm_active_enums::Status;
 use serde::{ Deserialize, Serialize };
+use utoipa::{ OpenApi, ToSchema };
+use validator::Validate;
+use crate::t;
 
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
 struct DroneQuery {
     #[serde(flatten)]
     pagination: PaginationParams,
+    #[serde(flatten)]
+    list: ListQuery,
 }
 
+impl Queryable for Drones {
+    fn fields() -> Vec<QueryField<drones::Column>> {
+        vec![
+            QueryField::new("name", drones::Column::Name, FieldKind::Text),
+            QueryField::new("model", drones::Column::Model, FieldKind::Text),
+            QueryField::new("status", drones::Column::Status, FieldKind::Enum(parse_enum::<Status>)),
+            QueryField::new("battery", drones::Column::Battery, FieldKind::Integer),
+            QueryField::new("activate", drones::Column::Activate, FieldKind::Integer),
+            QueryField::new("last_seen_at", drones::Column::LastSeenAt, FieldKind::DateTime)
+        ]
+    }
+
+    fn search_columns() -> Vec<drones::Column> {
+        vec![drones::Column::Name, drones::Column::Model]
+    }
+
+    fn default_sort() -> Vec<(drones::Column, Order)> {
+        vec![(drones::Column::Name, Order::Asc), (drones::Column::DroneId, Order::Asc)]
+    }
+}
+
+type DroneListParams = ListParams<Drones>;
+
 // struct DroneResponse {
 //     id: String,
 //     name: String,
@@ -39,89 +72,123 @@ struct DroneQuery {
 //     battery_level: Option<i32>,
 // }
 
+#[utoipa::path(
+    get,
+    path = "/api/drones",
+    tag = "drones",
+    params(PaginationParams, DroneListParams),
+    responses((status = 200, description = "无人机列表", body = ApiResponse<Page<drones::Model>>))
+)]
 async fn get_all_drones(
-    State(AppState { db }): State<AppState>,
-    Query(DroneQuery { pagination }): Query<DroneQuery>
+    State(AppState { db, .. }): State<AppState>,
+    ValidQuery(DroneQuery { pagination, list }): ValidQuery<DroneQuery>
 ) -> ApiResult<ApiResponse<Page<drones::Model>>> {
-    let paginator = Drones::find().paginate(&db, pagination.size);
+    let paginator = list.apply(Drones::find())?.paginate(&db, pagination.size);
 
     let total = paginator.num_items().await?;
-    let drones = paginator.fetch_page(pagination.page - 1).await?;
+    let drones = paginator.fetch_page(pagination.index()).await?;
 
     let page = Page::from_pagination(pagination, total, drones);
 
-    Ok(ApiResponse::ok("ok", Some(page)))
+    Ok(ApiResponse::ok(t!("drone.list"), Some(page)))
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/drones/available",
+    tag = "drones",
+    params(PaginationParams, DroneListParams),
+    responses((status = 200, description = "已启用的无人机列表", body = ApiResponse<Page<drones::Model>>))
+)]
 async fn get_all_available_drones(
-    State(AppState { db }): State<AppState>,
-    Query(DroneQuery { pagination }): Query<DroneQuery>
+    State(AppState { db, .. }): State<AppState>,
+    ValidQuery(DroneQuery { pagination, list }): ValidQuery<DroneQuery>
 ) -> ApiResult<ApiResponse<Page<drones::Model>>> {
-    let paginator = Drones::find()
-        .filter(drones::Column::Activate.eq(true))
+    let paginator = list
+        .apply(Drones::find().filter(drones::Column::Activate.eq(true)))?
         .paginate(&db, pagination.size);
 
     let total = paginator.num_items().await?;
-    let drones = paginator.fetch_page(pagination.page - 1).await?;
+    let drones = paginator.fetch_page(pagination.index()).await?;
 
     let page = Page::from_pagination(pagination, total, drones);
 
-    Ok(ApiResponse::ok("获取所有可用无人机成功", Some(page)))
+    Ok(ApiResponse::ok(t!("drone.available"), Some(page)))
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/drones/{id}",
+    tag = "drones",
+    params(("id" = String, Path, description = "无人机 ID")),
+    responses((status = 200, description = "无人机详情", body = ApiResponse<drones::Model>))
+)]
 async fn get_drone(
-    State(AppState { db }): State<AppState>,
-    Path(id): Path<String>
+    State(AppState { db, .. }): State<AppState>,
+    ValidPath(id): ValidPath<String>
 ) -> ApiResult<ApiResponse<drones::Model>> {
-    let drone = Drones::find_by_id(&id).one(&db).await.unwrap();
+    let drone = Drones::find_by_id(&id).one(&db).await?;
 
     if let Some(drone) = drone {
-        Ok(ApiResponse::ok("ok", Some(drone)))
+        Ok(ApiResponse::ok(t!("drone.detail"), Some(drone)))
     } else {
-        let msg = format!("Drone id {} not found", id);
-        Err(ApiError::Biz(msg))
+        let msg = t!("drone.not_found", id = id);
+        Err(ApiError::NotFound(msg))
     }
 }
 
-#[derive(Debug, Deserialize)]
+// 新无人机为空闲状态；之后的状态只随任务流转和维护接口变化，异常的无人机通过维护恢复
+#[derive(Debug, Deserialize, ToSchema, Validate)]
 struct DroneCreateRequest {
+    #[validate(length(min = 1, max = 50))]
     name: String,
+    #[validate(length(min = 1, max = 50))]
     model: String,
-    status: Status,
+    #[validate(range(max = 100))]
     battery: u8,
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/drones",
+    tag = "drones",
+    request_body = DroneCreateRequest,
+    responses((status = 200, description = "添加成功", body = MessageResponse))
+)]
 async fn add_drone(
-    State(AppState { db }): State<AppState>,
-    Json(data): Json<DroneCreateRequest>
+    _: Authorized<AdminOnly>,
+    State(AppState { db, .. }): State<AppState>,
+    ValidJson(data): ValidJson<DroneCreateRequest>
 ) -> ApiResult<ApiResponse<()>> {
     let drone = DronesActiveModel {
         drone_id: ActiveValue::set(xid::new().to_string()),
         name: ActiveValue::set(data.name),
         model: ActiveValue::set(data.model),
-        status: ActiveValue::set(data.status),
+        status: ActiveValue::set(Status::Idle),
         battery: ActiveValue::set(data.battery),
         // created_at: ActiveValue::set(Some(Utc::now().naive_utc())),
         ..Default::default()
     };
 
-    drone.insert(&db).await.unwrap();
+    drone.insert(&db).await?;
 
-    Ok(ApiResponse::ok("无人机信息添加成功", None))
+    Ok(ApiResponse::ok(t!("drone.created"), None))
 }
 
 // 修改用户时的请求体
-#[derive(Deserialize)]
+#[derive(Deserialize, ToSchema, Validate)]
 struct DroneUpdateRequest {
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[validate(length(min = 1, max = 50))]
     name: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[validate(length(min = 1, max = 50))]
     model: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    status: Option<Status>,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[validate(range(max = 100))]
     battery: Option<u8>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[validate(range(min = 0, max = 1))]
     activate: Option<i8>,
 }
 
@@ -133,9 +200,6 @@ impl DroneUpdateRequest {
         if let Some(ref model) = self.model {
             drone.model = ActiveValue::set(model.clone());
         }
-        if let Some(ref status) = self.status {
-            drone.status = ActiveValue::set(status.clone());
-        }
         if let Some(ref battery) = self.battery {
             drone.battery = ActiveValue::set(*battery);
         }
@@ -145,51 +209,82 @@ impl DroneUpdateRequest {
     }
 }
 
+#[utoipa::path(
+    put,
+    path = "/api/drones/{id}",
+    tag = "drones",
+    params(("id" = String, Path, description = "无人机 ID")),
+    request_body = DroneUpdateRequest,
+    responses((status = 200, description = "更新成功", body = MessageResponse))
+)]
 async fn update_drone(
-    State(AppState { db }): State<AppState>,
-    Path(id): Path<String>,
-    Json(data): Json<DroneUpdateRequest>
+    _: Authorized<AdminOnly>,
+    State(AppState { db, .. }): State<AppState>,
+    ValidPath(id): ValidPath<String>,
+    ValidJson(data): ValidJson<DroneUpdateRequest>
 ) -> ApiResult<ApiResponse<()>> {
     let drone = Drones::find_by_id(&id).one(&db);
-    if let Some(drone) = drone.await.unwrap() {
+    if let Some(drone) = drone.await? {
         let mut drone = drone.into_active_model();
 
         data.apply_to(&mut drone);
 
-        drone.update(&db).await.unwrap();
-        Ok(ApiResponse::ok("更新无人机成功", None))
+        drone.update(&db).await?;
+        Ok(ApiResponse::ok(t!("drone.updated"), None))
     } else {
-        Err(ApiError::Biz("无人机未找到".to_string()))
+        Err(ApiError::NotFound(t!("drone.not_found", id = id)))
     }
 }
 
+#[utoipa::path(
+    delete,
+    path = "/api/drones/{id}",
+    tag = "drones",
+    params(("id" = String, Path, description = "无人机 ID")),
+    responses((status = 200, description = "删除成功，指定了该无人机的任务模板会被暂停", body = MessageResponse))
+)]
 async fn delete_drone(
-    State(AppState { db }): State<AppState>,
-    Path(id): Path<String>
+    _: Authorized<AdminOnly>,
+    State(AppState { db, .. }): State<AppState>,
+    ValidPath(id): ValidPath<String>
 ) -> ApiResult<ApiResponse<()>> {
-    let drone = Drones::find_by_id(&id).one(&db).await.unwrap();
+    let txn = db.begin().await?;
+    let drone = Drones::find_by_id(&id).one(&txn).await?;
 
     if let Some(drone) = drone {
-        drone.delete(&db).await.unwrap();
-        Ok(ApiResponse::ok("删除无人机成功", None))
+        // 删除后模板的 drone_id 会被置空，先暂停指定了该无人机的模板
+        MissionTemplates::update_many()
+            .col_expr(mission_templates::Column::Paused, Expr::value(true))
+            .col_expr(mission_templates::Column::NextRunAt, Expr::value(Option::<DateTime>::None))
+            .filter(mission_templates::Column::DroneId.eq(&id))
+            .exec(&txn).await?;
+        drone.delete(&txn).await?;
+        txn.commit().await?;
+        Ok(ApiResponse::ok(t!("drone.deleted"), None))
     } else {
-        Err(ApiError::Biz("无人机未找到".to_string()))
+        Err(ApiError::NotFound(t!("drone.not_found", id = id)))
     }
 }
 
-#[derive(Debug, Serialize, FromQueryResult)]
+#[derive(Debug, Serialize, FromQueryResult, ToSchema)]
 struct DroneStatusItem {
     status: String,
     count: i64,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 struct DroneStatusResponse {
     categories: Vec<DroneStatusItem>,
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/drones/status",
+    tag = "drones",
+    responses((status = 200, description = "按状态统计的无人机数量", body = ApiResponse<DroneStatusResponse>))
+)]
 async fn get_drone_status(State(
-    AppState { db },
+    AppState { db, .. },
 ): State<AppState>) -> ApiResult<ApiResponse<DroneStatusResponse>> {
     let result = Drones::find()
         .select_only()
@@ -199,9 +294,24 @@ async fn get_drone_status(State(
         .into_model::<DroneStatusItem>()
         .all(&db).await?;
 
-    Ok(ApiResponse::ok("获取无人机状态分布成功", Some(DroneStatusResponse { categories: result })))
+    Ok(ApiResponse::ok(t!("drone.status_summary"), Some(DroneStatusResponse { categories: result })))
 }
 
+#[derive(OpenApi)]
+#[openapi(
+    paths(
+        get_all_drones,
+        add_drone,
+        get_drone,
+        update_drone,
+        delete_drone,
+        get_all_available_drones,
+        get_drone_status
+    ),
+    tags((name = "drones", description = "无人机管理"))
+)]
+pub struct DroneApi;
+
 pub fn create_drone_router() -> Router<AppState> {
     Router::new()
         .route("/", get(get_all_drones))
@@ -1,70 +1,190 @@
-use axum::extract::{ Path, Query, State, Json };
+use axum::extract::State;
 use axum::Router;
 use axum::routing::{ delete, get, post, put };
 use sea_orm::{
     ActiveModelTrait,
     ActiveValue,
+    ColumnTrait,
     EntityTrait,
     IntoActiveModel,
     ModelTrait,
     PaginatorTrait,
+    Order,
+    QueryFilter,
+    QuerySelect,
+    QueryTrait,
+    Select,
 };
 
 use crate::app::AppState;
-use crate::common::response::ApiResponse;
+use crate::common::auth::{ self, AdminOnly, Authorized };
+use crate::common::jwt::Claims;
+use crate::common::response::{ ApiResponse, MessageResponse };
 use crate::entity::events::ActiveModel as EventsActiveModel;
-use crate::entity::events;
-use crate::entity::prelude::Events;
-use crate::common::page::{ Page, PaginationParams };
+use crate::entity::{ events, missions };
+use crate::entity::prelude::{ Events, Missions };
+use crate::common::cursor::{ self, Cursor, CursorId, Keyset };
+use crate::common::page::{ CursorParams, Listing, Page, PaginationParams };
+use crate::common::query::{ parse_enum, FieldKind, ListParams, ListQuery, QueryField, Queryable };
 use crate::common::result::{ ApiError, ApiResult };
+use crate::common::validation::{ ValidJson, ValidPath, ValidQuery };
 use crate::entity::sea_orm_active_enums::EventType;
-use serde::{ Deserialize };
+use crate::hub::{ EventCreated, HubMessage };
+use serde::Deserialize;
+use utoipa::{ OpenApi, ToSchema };
+use validator::Validate;
+use crate::t;
 
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
 struct EventQuery {
     #[serde(flatten)]
     pagination: PaginationParams,
+    #[serde(flatten)]
+    cursor: CursorParams,
+    #[serde(flatten)]
+    list: ListQuery,
+}
+
+impl Queryable for Events {
+    fn fields() -> Vec<QueryField<events::Column>> {
+        vec![
+            QueryField::new("mission_id", events::Column::MissionId, FieldKind::Text),
+            QueryField::new(
+                "event_type",
+                events::Column::EventType,
+                FieldKind::Enum(parse_enum::<EventType>)
+            ),
+            QueryField::new("created_at", events::Column::CreatedAt, FieldKind::DateTime)
+        ]
+    }
+
+    fn search_columns() -> Vec<events::Column> {
+        vec![events::Column::Message]
+    }
+
+    fn default_sort() -> Vec<(events::Column, Order)> {
+        vec![(events::Column::CreatedAt, Order::Desc), (events::Column::EventId, Order::Desc)]
+    }
 }
 
+type EventListParams = ListParams<Events>;
+
+impl Keyset for Events {
+    fn time_column() -> Option<events::Column> {
+        Some(events::Column::CreatedAt)
+    }
+
+    fn id_column() -> events::Column {
+        events::Column::EventId
+    }
+
+    fn cursor_of(model: &events::Model) -> Cursor {
+        Cursor { time: Some(model.created_at), id: CursorId::Text(model.event_id.clone()) }
+    }
+}
+
+// 普通用户只能查看自己任务的事件，与任务列表接口的可见范围一致
+fn visible_events(claims: &Claims) -> Select<Events> {
+    let select = Events::find();
+    if claims.is_admin() {
+        return select;
+    }
+
+    let own_missions = Missions::find()
+        .select_only()
+        .column(missions::Column::MissionId)
+        .filter(missions::Column::UserId.eq(&claims.user_id))
+        .into_query();
+    select.filter(events::Column::MissionId.in_subquery(own_missions))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/events",
+    tag = "events",
+    params(PaginationParams, CursorParams, EventListParams),
+    responses((status = 200, description = "事件列表，带 cursor 或 limit 时使用游标分页", body = ApiResponse<Listing<events::Model>>))
+)]
 async fn get_all_events(
-    State(AppState { db }): State<AppState>,
-    Query(EventQuery { pagination }): Query<EventQuery>
-) -> ApiResult<ApiResponse<Page<events::Model>>> {
-    let paginator = Events::find().paginate(&db, pagination.size);
+    claims: Claims,
+    State(AppState { db, .. }): State<AppState>,
+    ValidQuery(EventQuery { pagination, cursor, list }): ValidQuery<EventQuery>
+) -> ApiResult<ApiResponse<Listing<events::Model>>> {
+    let events = visible_events(&claims);
+
+    if cursor.is_active() {
+        let select = list.apply_for_cursor(events)?;
+        let page = cursor::fetch(&db, select, &cursor).await?;
+        return Ok(ApiResponse::ok(t!("event.list"), Some(Listing::Cursor(page))));
+    }
+
+    let paginator = list.apply(events)?.paginate(&db, pagination.size);
     let total = paginator.num_items().await?;
-    let events = paginator.fetch_page(pagination.page - 1).await?;
+    let events = paginator.fetch_page(pagination.index()).await?;
 
     let page = Page::from_pagination(pagination, total, events);
 
-    Ok(ApiResponse::ok("获取事件列表成功", Some(page)))
+    Ok(ApiResponse::ok(t!("event.list"), Some(Listing::Page(page))))
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/events/{id}",
+    tag = "events",
+    params(("id" = String, Path, description = "事件 ID")),
+    responses((status = 200, description = "事件详情", body = ApiResponse<events::Model>))
+)]
 async fn get_event(
-    State(AppState { db }): State<AppState>,
-    Path(id): Path<String>
+    claims: Claims,
+    State(AppState { db, hub, .. }): State<AppState>,
+    ValidPath(id): ValidPath<String>
 ) -> ApiResult<ApiResponse<events::Model>> {
-    let event = Events::find_by_id(&id).one(&db).await.unwrap();
+    let event = Events::find_by_id(&id).one(&db).await?;
 
     if let Some(event) = event {
-        Ok(ApiResponse::ok("获取事件成功", Some(event)))
+        if !claims.is_admin() {
+            let mission = event.find_related(Missions).one(&db).await?;
+            if mission.is_none_or(|mission| mission.user_id != claims.user_id) {
+                return Err(auth::deny(&db, &hub, &claims, &format!("查看事件<{}>", id)).await);
+            }
+        }
+        Ok(ApiResponse::ok(t!("event.detail"), Some(event)))
     } else {
-        let msg = format!("Event id {} not found", id);
-        Err(ApiError::Biz(msg))
+        let msg = t!("event.not_found", id = id);
+        Err(ApiError::NotFound(msg))
     }
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema, Validate)]
 struct EventCreateRequest {
+    #[validate(length(min = 1, max = 32))]
     mission_id: String,
     event_type: EventType,
+    #[validate(length(min = 1))]
     message: String,
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/events",
+    tag = "events",
+    request_body = EventCreateRequest,
+    responses((status = 200, description = "添加成功", body = MessageResponse))
+)]
 async fn add_event(
-    State(AppState { db }): State<AppState>,
-    Json(data): Json<EventCreateRequest>
+    claims: Claims,
+    State(AppState { db, hub, .. }): State<AppState>,
+    ValidJson(data): ValidJson<EventCreateRequest>
 ) -> ApiResult<ApiResponse<()>> {
+    let Some(mission) = Missions::find_by_id(&data.mission_id).one(&db).await? else {
+        return Err(ApiError::InvalidReference(t!("mission.not_found", id = data.mission_id)));
+    };
+    if !claims.is_self_or_admin(&mission.user_id) {
+        let action = format!("为任务<{}>添加事件", mission.mission_id);
+        return Err(auth::deny(&db, &hub, &claims, &action).await);
+    }
+
     let event = EventsActiveModel {
         event_id: ActiveValue::set(xid::new().to_string()),
         mission_id: ActiveValue::set(data.mission_id),
@@ -73,15 +193,24 @@ async fn add_event(
         ..Default::default()
     };
 
-    event.insert(&db).await.unwrap();
+    let event = event.insert(&db).await?;
+
+    hub.publish(
+        HubMessage::Event(EventCreated {
+            drone_id: mission.drone_id,
+            user_id: mission.user_id,
+            event,
+        })
+    );
 
-    Ok(ApiResponse::ok("事件信息添加成功", None))
+    Ok(ApiResponse::ok(t!("event.created"), None))
 }
 
 // 修改用户时的请求体
-#[derive(Deserialize)]
+#[derive(Deserialize, ToSchema, Validate)]
 struct EventUpdateRequest {
     event_type: Option<EventType>,
+    #[validate(length(min = 1))]
     message: Option<String>,
 }
 
@@ -96,38 +225,68 @@ impl EventUpdateRequest {
     }
 }
 
+#[utoipa::path(
+    put,
+    path = "/api/events/{id}",
+    tag = "events",
+    params(("id" = String, Path, description = "事件 ID")),
+    request_body = EventUpdateRequest,
+    responses((status = 200, description = "更新成功", body = MessageResponse))
+)]
 async fn update_event(
-    State(AppState { db }): State<AppState>,
-    Path(id): Path<String>,
-    Json(data): Json<EventUpdateRequest>
+    _: Authorized<AdminOnly>,
+    State(AppState { db, .. }): State<AppState>,
+    ValidPath(id): ValidPath<String>,
+    ValidJson(data): ValidJson<EventUpdateRequest>
 ) -> ApiResult<ApiResponse<()>> {
     let event = Events::find_by_id(&id).one(&db);
-    if let Some(event) = event.await.unwrap() {
+    if let Some(event) = event.await? {
         let mut event = event.into_active_model();
 
         data.apply_to(&mut event);
 
-        event.update(&db).await.unwrap();
-        Ok(ApiResponse::ok("更新事件成功", None))
+        event.update(&db).await?;
+        Ok(ApiResponse::ok(t!("event.updated"), None))
     } else {
-        Err(ApiError::Biz("事件未找到".to_string()))
+        Err(ApiError::NotFound(t!("event.not_found", id = id)))
     }
 }
 
+#[utoipa::path(
+    delete,
+    path = "/api/events/{id}",
+    tag = "events",
+    params(("id" = String, Path, description = "事件 ID")),
+    responses((status = 200, description = "删除成功", body = MessageResponse))
+)]
 async fn delete_event(
-    State(AppState { db }): State<AppState>,
-    Path(id): Path<String>
+    _: Authorized<AdminOnly>,
+    State(AppState { db, .. }): State<AppState>,
+    ValidPath(id): ValidPath<String>
 ) -> ApiResult<ApiResponse<()>> {
-    let event = Events::find_by_id(&id).one(&db).await.unwrap();
+    let event = Events::find_by_id(&id).one(&db).await?;
 
     if let Some(event) = event {
-        event.delete(&db).await.unwrap();
-        Ok(ApiResponse::ok("删除事件成功", None))
+        event.delete(&db).await?;
+        Ok(ApiResponse::ok(t!("event.deleted"), None))
     } else {
-        Err(ApiError::Biz("事件未找到".to_string()))
+        Err(ApiError::NotFound(t!("event.not_found", id = id)))
     }
 }
 
+#[derive(OpenApi)]
+#[openapi(
+    paths(
+        get_all_events,
+        add_event,
+        get_event,
+        update_event,
+        delete_event
+    ),
+    tags((name = "events", description = "任务事件"))
+)]
+pub struct EventApi;
+
 pub fn create_event_router() -> Router<AppState> {
     Router::new()
         .route("/", get(get_all_events))
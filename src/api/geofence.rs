@@ -0,0 +1,465 @@
+use axum::extract::State;
+use axum::Router;
+use axum::routing::get;
+use sea_orm::entity::prelude::*;
+use sea_orm::{ ActiveValue, Condition, IntoActiveModel, Order, QueryFilter, QueryOrder };
+use serde::{ Deserialize, Serialize };
+use utoipa::{ OpenApi, ToSchema };
+use validator::{ Validate, ValidationError };
+
+use crate::api::waypoint::load_waypoints;
+use crate::app::AppState;
+use crate::common::auth::{ self, AdminOnly, Authorized };
+use crate::common::geo::{ Point, Shape };
+use crate::common::jwt::Claims;
+use crate::common::page::{ Page, PaginationParams };
+use crate::common::query::{ parse_enum, FieldKind, ListParams, ListQuery, QueryField, Queryable };
+use crate::common::response::{ ApiResponse, MessageResponse };
+use crate::common::result::{ ApiError, ApiResult };
+use crate::common::time;
+use crate::common::validation::{ self, ValidJson, ValidPath, ValidQuery };
+use crate::entity::geofences::{ ActiveModel as GeofencesActiveModel, Vertex, Vertices };
+use crate::entity::prelude::{ Geofences, Missions };
+use crate::entity::sea_orm_active_enums::{ GeofenceKind, GeofenceShape };
+use crate::entity::{ geofences, mission_waypoints };
+use crate::t;
+
+#[derive(Debug, Deserialize)]
+struct GeofenceQuery {
+    #[serde(flatten)]
+    pagination: PaginationParams,
+    #[serde(flatten)]
+    list: ListQuery,
+}
+
+impl Queryable for Geofences {
+    fn fields() -> Vec<QueryField<geofences::Column>> {
+        vec![
+            QueryField::new("name", geofences::Column::Name, FieldKind::Text),
+            QueryField::new("shape", geofences::Column::Shape, FieldKind::Enum(parse_enum::<GeofenceShape>)),
+            QueryField::new("kind", geofences::Column::Kind, FieldKind::Enum(parse_enum::<GeofenceKind>)),
+            QueryField::new("active_from", geofences::Column::ActiveFrom, FieldKind::DateTime),
+            QueryField::new("active_until", geofences::Column::ActiveUntil, FieldKind::DateTime),
+            QueryField::new("created_by", geofences::Column::CreatedBy, FieldKind::Text),
+            QueryField::new("created_at", geofences::Column::CreatedAt, FieldKind::DateTime)
+        ]
+    }
+
+    fn search_columns() -> Vec<geofences::Column> {
+        vec![geofences::Column::Name]
+    }
+
+    fn default_sort() -> Vec<(geofences::Column, Order)> {
+        vec![(geofences::Column::CreatedAt, Order::Desc), (geofences::Column::GeofenceId, Order::Desc)]
+    }
+}
+
+type GeofenceListParams = ListParams<Geofences>;
+
+#[derive(Debug, Serialize, Deserialize, ToSchema, Validate)]
+struct VertexRequest {
+    #[validate(custom(function = "validation::latitude"))]
+    lat: Decimal,
+    #[validate(custom(function = "validation::longitude"))]
+    lng: Decimal,
+}
+
+// 圆形需要圆心和半径，多边形需要顶点；生效时间段可只设置一端
+#[derive(Debug, Deserialize, ToSchema, Validate)]
+#[serde(rename_all = "camelCase")]
+#[validate(schema(function = "validate_geofence"))]
+struct GeofenceRequest {
+    #[validate(length(min = 1, max = 100))]
+    name: String,
+    shape: GeofenceShape,
+    kind: GeofenceKind,
+    #[validate(custom(function = "validation::latitude"))]
+    center_lat: Option<Decimal>,
+    #[validate(custom(function = "validation::longitude"))]
+    center_lng: Option<Decimal>,
+    /// 圆形半径（米）
+    #[validate(range(exclusive_min = 0.0, max = 100000.0))]
+    radius: Option<f32>,
+    /// 多边形顶点，按顺序首尾相连
+    #[validate(length(min = 3, max = 100), nested)]
+    vertices: Option<Vec<VertexRequest>>,
+    active_from: Option<DateTime>,
+    active_until: Option<DateTime>,
+}
+
+fn validate_geofence(data: &GeofenceRequest) -> Result<(), ValidationError> {
+    match data.shape {
+        GeofenceShape::Circle if
+            data.center_lat.is_none() ||
+            data.center_lng.is_none() ||
+            data.radius.is_none()
+        => {
+            return Err(ValidationError::new("geofence_circle"));
+        }
+        GeofenceShape::Polygon if data.vertices.is_none() => {
+            return Err(ValidationError::new("geofence_polygon"));
+        }
+        _ => {}
+    }
+    if let (Some(from), Some(until)) = (data.active_from, data.active_until) && from >= until {
+        return Err(ValidationError::new("time_window"));
+    }
+    Ok(())
+}
+
+impl GeofenceRequest {
+    // 只保存与形状对应的字段
+    fn apply_to(self, geofence: &mut GeofencesActiveModel) {
+        let circle = self.shape == GeofenceShape::Circle;
+        let vertices = self.vertices
+            .filter(|_| !circle)
+            .map(|vertices| {
+                Vertices(
+                    vertices
+                        .into_iter()
+                        .map(|vertex| Vertex { lat: vertex.lat, lng: vertex.lng })
+                        .collect()
+                )
+            });
+
+        geofence.name = ActiveValue::set(self.name);
+        geofence.shape = ActiveValue::set(self.shape);
+        geofence.kind = ActiveValue::set(self.kind);
+        geofence.center_lat = ActiveValue::set(self.center_lat.filter(|_| circle));
+        geofence.center_lng = ActiveValue::set(self.center_lng.filter(|_| circle));
+        geofence.radius = ActiveValue::set(self.radius.filter(|_| circle));
+        geofence.vertices = ActiveValue::set(vertices);
+        geofence.active_from = ActiveValue::set(self.active_from);
+        geofence.active_until = ActiveValue::set(self.active_until);
+    }
+}
+
+fn shape_of(geofence: &geofences::Model) -> Option<Shape> {
+    match geofence.shape {
+        GeofenceShape::Circle => {
+            let center = Point::new(geofence.center_lat.as_ref()?, geofence.center_lng.as_ref()?);
+            Some(Shape::Circle { center, radius: f64::from(geofence.radius?) })
+        }
+        GeofenceShape::Polygon => {
+            let vertices = geofence.vertices.as_ref()?;
+            Some(Shape::Polygon(vertices.0.iter().map(|vertex| Point::new(&vertex.lat, &vertex.lng)).collect()))
+        }
+    }
+}
+
+// 当前时间处于生效时间段内的围栏
+fn active_condition(now: DateTime) -> Condition {
+    Condition::all()
+        .add(
+            Condition::any()
+                .add(geofences::Column::ActiveFrom.is_null())
+                .add(geofences::Column::ActiveFrom.lte(now))
+        )
+        .add(
+            Condition::any()
+                .add(geofences::Column::ActiveUntil.is_null())
+                .add(geofences::Column::ActiveUntil.gt(now))
+        )
+}
+
+// 违反禁飞区的位置：任务目标点、航点或两个航点之间的航段（seq 为航段起点）
+#[derive(Debug, Clone, Copy, Serialize, ToSchema)]
+pub(super) enum ViolationReason {
+    Target,
+    Waypoint,
+    Route,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub(super) struct GeofenceViolation {
+    geofence_id: String,
+    name: String,
+    reason: ViolationReason,
+    seq: Option<i32>,
+}
+
+// 不在任何允许飞行区内的点，seq 为空表示任务目标点
+#[derive(Debug, Clone, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub(super) struct RoutePoint {
+    seq: Option<i32>,
+    lat: Decimal,
+    lng: Decimal,
+}
+
+// 进入禁飞区的记为违规，存在允许飞行区时超出范围的点只作提示
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub(super) struct PreflightReport {
+    passed: bool,
+    violations: Vec<GeofenceViolation>,
+    outside_inclusion: Vec<RoutePoint>,
+}
+
+impl PreflightReport {
+    // 存在违规时拒绝，并在错误详情中列出违反的围栏
+    pub(super) fn ensure_clear(self) -> ApiResult<Self> {
+        if self.passed {
+            return Ok(self);
+        }
+
+        let mut names: Vec<&str> = self.violations
+            .iter()
+            .map(|violation| violation.name.as_str())
+            .collect();
+        names.dedup();
+        let message = t!("geofence.violated", names = names.join(", "));
+
+        let details = serde_json::to_value(&self.violations).map_err(anyhow::Error::from)?;
+        Err(ApiError::GeofenceViolation(message, details))
+    }
+}
+
+// 检查任务目标点和航点航线是否进入当前生效的禁飞区、是否超出允许飞行区
+pub(super) async fn check_route<C: ConnectionTrait>(
+    conn: &C,
+    target: Option<(Decimal, Decimal)>,
+    waypoints: &[mission_waypoints::Model]
+) -> ApiResult<PreflightReport> {
+    let geofences = Geofences::find().filter(active_condition(time::now())).all(conn).await?;
+
+    let target = target.map(|(lat, lng)| RoutePoint { seq: None, lat, lng });
+    let points: Vec<RoutePoint> = target
+        .into_iter()
+        .chain(
+            waypoints.iter().map(|waypoint| RoutePoint {
+                seq: Some(waypoint.seq),
+                lat: waypoint.lat,
+                lng: waypoint.lng,
+            })
+        )
+        .collect();
+    let located: Vec<(&RoutePoint, Point)> = points
+        .iter()
+        .map(|point| (point, Point::new(&point.lat, &point.lng)))
+        .collect();
+    let route: Vec<&(&RoutePoint, Point)> = located
+        .iter()
+        .filter(|(point, _)| point.seq.is_some())
+        .collect();
+
+    let mut violations = Vec::new();
+    let mut inclusions = Vec::new();
+    for geofence in &geofences {
+        let Some(shape) = shape_of(geofence) else {
+            continue;
+        };
+        if geofence.kind == GeofenceKind::Inclusion {
+            inclusions.push(shape);
+            continue;
+        }
+
+        let violation = |reason, seq| GeofenceViolation {
+            geofence_id: geofence.geofence_id.clone(),
+            name: geofence.name.clone(),
+            reason,
+            seq,
+        };
+        for (point, location) in &located {
+            if shape.contains(location) {
+                let reason = match point.seq {
+                    Some(_) => ViolationReason::Waypoint,
+                    None => ViolationReason::Target,
+                };
+                violations.push(violation(reason, point.seq));
+            }
+        }
+        // 两端都在区域外但中途穿过的航段
+        for leg in route.windows(2) {
+            let ((from, a), (_, b)) = (leg[0], leg[1]);
+            if !shape.contains(a) && !shape.contains(b) && shape.intersects(a, b) {
+                violations.push(violation(ViolationReason::Route, from.seq));
+            }
+        }
+    }
+
+    let outside_inclusion = if inclusions.is_empty() {
+        Vec::new()
+    } else {
+        located
+            .iter()
+            .filter(|(_, location)| !inclusions.iter().any(|shape| shape.contains(location)))
+            .map(|(point, _)| (*point).clone())
+            .collect()
+    };
+
+    Ok(PreflightReport { passed: violations.is_empty(), violations, outside_inclusion })
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/geofences",
+    tag = "geofences",
+    params(PaginationParams, GeofenceListParams),
+    responses((status = 200, description = "围栏列表", body = ApiResponse<Page<geofences::Model>>))
+)]
+async fn get_all_geofences(
+    State(AppState { db, .. }): State<AppState>,
+    ValidQuery(GeofenceQuery { pagination, list }): ValidQuery<GeofenceQuery>
+) -> ApiResult<ApiResponse<Page<geofences::Model>>> {
+    let paginator = list.apply(Geofences::find())?.paginate(&db, pagination.size);
+
+    let total = paginator.num_items().await?;
+    let geofences = paginator.fetch_page(pagination.index()).await?;
+
+    let page = Page::from_pagination(pagination, total, geofences);
+    Ok(ApiResponse::ok(t!("geofence.list"), Some(page)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/geofences/active",
+    tag = "geofences",
+    responses((status = 200, description = "当前生效的围栏", body = ApiResponse<Vec<geofences::Model>>))
+)]
+async fn get_active_geofences(
+    State(AppState { db, .. }): State<AppState>
+) -> ApiResult<ApiResponse<Vec<geofences::Model>>> {
+    let geofences = Geofences::find()
+        .filter(active_condition(time::now()))
+        .order_by_asc(geofences::Column::Name)
+        .all(&db).await?;
+
+    Ok(ApiResponse::ok(t!("geofence.list"), Some(geofences)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/geofences/{id}",
+    tag = "geofences",
+    params(("id" = String, Path, description = "围栏 ID")),
+    responses((status = 200, description = "围栏详情", body = ApiResponse<geofences::Model>))
+)]
+async fn get_geofence(
+    State(AppState { db, .. }): State<AppState>,
+    ValidPath(id): ValidPath<String>
+) -> ApiResult<ApiResponse<geofences::Model>> {
+    let Some(geofence) = Geofences::find_by_id(&id).one(&db).await? else {
+        return Err(ApiError::NotFound(t!("geofence.not_found", id = id)));
+    };
+    Ok(ApiResponse::ok(t!("geofence.detail"), Some(geofence)))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/geofences",
+    tag = "geofences",
+    request_body = GeofenceRequest,
+    responses((status = 200, description = "添加成功", body = ApiResponse<geofences::Model>))
+)]
+async fn add_geofence(
+    Authorized { claims, .. }: Authorized<AdminOnly>,
+    State(AppState { db, .. }): State<AppState>,
+    ValidJson(data): ValidJson<GeofenceRequest>
+) -> ApiResult<ApiResponse<geofences::Model>> {
+    let mut geofence = GeofencesActiveModel {
+        geofence_id: ActiveValue::set(xid::new().to_string()),
+        created_by: ActiveValue::set(claims.user_id),
+        ..Default::default()
+    };
+    data.apply_to(&mut geofence);
+
+    let geofence = geofence.insert(&db).await?;
+    Ok(ApiResponse::ok(t!("geofence.created"), Some(geofence)))
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/geofences/{id}",
+    tag = "geofences",
+    params(("id" = String, Path, description = "围栏 ID")),
+    request_body = GeofenceRequest,
+    responses((status = 200, description = "更新成功，围栏定义整体替换", body = ApiResponse<geofences::Model>))
+)]
+async fn update_geofence(
+    _: Authorized<AdminOnly>,
+    State(AppState { db, .. }): State<AppState>,
+    ValidPath(id): ValidPath<String>,
+    ValidJson(data): ValidJson<GeofenceRequest>
+) -> ApiResult<ApiResponse<geofences::Model>> {
+    let Some(geofence) = Geofences::find_by_id(&id).one(&db).await? else {
+        return Err(ApiError::NotFound(t!("geofence.not_found", id = id)));
+    };
+
+    let mut geofence = geofence.into_active_model();
+    data.apply_to(&mut geofence);
+
+    let geofence = geofence.update(&db).await?;
+    Ok(ApiResponse::ok(t!("geofence.updated"), Some(geofence)))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/geofences/{id}",
+    tag = "geofences",
+    params(("id" = String, Path, description = "围栏 ID")),
+    responses((status = 200, description = "删除成功", body = MessageResponse))
+)]
+async fn delete_geofence(
+    _: Authorized<AdminOnly>,
+    State(AppState { db, .. }): State<AppState>,
+    ValidPath(id): ValidPath<String>
+) -> ApiResult<ApiResponse<()>> {
+    let result = Geofences::delete_by_id(&id).exec(&db).await?;
+    if result.rows_affected == 0 {
+        return Err(ApiError::NotFound(t!("geofence.not_found", id = id)));
+    }
+    Ok(ApiResponse::ok(t!("geofence.deleted"), None))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/missions/{id}/preflight",
+    tag = "missions",
+    params(("id" = String, Path, description = "任务 ID")),
+    responses((status = 200, description = "起飞前围栏检查结果", body = ApiResponse<PreflightReport>))
+)]
+async fn preflight_mission(
+    claims: Claims,
+    State(AppState { db, hub, .. }): State<AppState>,
+    ValidPath(id): ValidPath<String>
+) -> ApiResult<ApiResponse<PreflightReport>> {
+    let Some(mission) = Missions::find_by_id(&id).one(&db).await? else {
+        return Err(ApiError::NotFound(t!("mission.not_found", id = id)));
+    };
+    if !claims.is_self_or_admin(&mission.user_id) {
+        return Err(auth::deny(&db, &hub, &claims, &format!("检查任务<{}>", id)).await);
+    }
+
+    let waypoints = load_waypoints(&db, &id).await?;
+    let report = check_route(&db, mission.target_lat.zip(mission.target_lng), &waypoints).await?;
+
+    Ok(ApiResponse::ok(t!("geofence.preflight"), Some(report)))
+}
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(
+        get_all_geofences,
+        get_active_geofences,
+        get_geofence,
+        add_geofence,
+        update_geofence,
+        delete_geofence,
+        preflight_mission
+    ),
+    tags((name = "geofences", description = "地理围栏与禁飞区"))
+)]
+pub struct GeofenceApi;
+
+pub fn create_geofence_router() -> Router<AppState> {
+    Router::new()
+        .route("/", get(get_all_geofences).post(add_geofence))
+        .route("/active", get(get_active_geofences))
+        .route("/{id}", get(get_geofence).put(update_geofence).delete(delete_geofence))
+}
+
+pub fn create_preflight_router() -> Router<AppState> {
+    Router::new().route("/{id}/preflight", get(preflight_mission))
+}
@@ -1,36 +1,78 @@
-use axum::extract::{ Path, Query, State, Json };
+use axum::extract::State;
 use axum::Router;
 use axum::routing::{ delete, get, post, put };
 use sea_orm::{
     ActiveModelTrait,
     ActiveValue,
-    ColumnTrait,
     EntityTrait,
     IntoActiveModel,
+    LoaderTrait,
     ModelTrait,
     PaginatorTrait,
-    QueryFilter,
-    FromQueryResult,
+    Order,
+    QueryOrder,
     QuerySelect,
+    TransactionTrait,
 };
 use sea_orm::prelude::*;
 use crate::app::AppState;
-use crate::common::response::ApiResponse;
+use crate::common::auth::{ AdminOnly, Authorized };
+use crate::common::response::{ ApiResponse, MessageResponse };
+use crate::common::jwt::Claims;
+use crate::entity::incident_status_changes::ActiveModel as IncidentStatusChangesActiveModel;
 use crate::entity::incidents::ActiveModel as IncidentsActiveModel;
-use crate::entity::incidents;
-use crate::entity::prelude::Incidents;
-use crate::common::page::{ Page, PaginationParams };
+use crate::entity::{ incident_status_changes, incidents, missions };
+use crate::entity::prelude::{ Events, IncidentStatusChanges, Incidents, Missions };
+use crate::common::geo::{ self, BoundingBox };
+use crate::common::page::{ deserialize_number, deserialize_optional_number, Page, PaginationParams };
+use crate::common::query::{ parse_enum, FieldKind, ListParams, ListQuery, QueryField, Queryable };
 use crate::common::result::{ ApiError, ApiResult };
-use crate::entity::sea_orm_active_enums::IncidentStatus;
+use crate::common::validation::{ self, ValidJson, ValidPath, ValidQuery };
+use crate::entity::sea_orm_active_enums::{ EventType, IncidentStatus, Status };
 use serde::{ Deserialize, Serialize };
+use utoipa::{ IntoParams, OpenApi, ToSchema };
+use validator::Validate;
+use crate::t;
 
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
 struct IncidentQuery {
     #[serde(flatten)]
     pagination: PaginationParams,
+    #[serde(flatten)]
+    list: ListQuery,
+}
+
+impl Queryable for Incidents {
+    fn fields() -> Vec<QueryField<incidents::Column>> {
+        vec![
+            QueryField::new("title", incidents::Column::Title, FieldKind::Text),
+            QueryField::new(
+                "status",
+                incidents::Column::Status,
+                FieldKind::Enum(parse_enum::<IncidentStatus>)
+            ),
+            QueryField::new("severity", incidents::Column::Severity, FieldKind::Integer),
+            QueryField::new("radius", incidents::Column::Radius, FieldKind::Float),
+            QueryField::new("lat", incidents::Column::Lat, FieldKind::Decimal),
+            QueryField::new("lng", incidents::Column::Lng, FieldKind::Decimal),
+            QueryField::new("created_by", incidents::Column::CreatedBy, FieldKind::Text),
+            QueryField::new("created_at", incidents::Column::CreatedAt, FieldKind::DateTime),
+            QueryField::new("updated_at", incidents::Column::UpdatedAt, FieldKind::DateTime)
+        ]
+    }
+
+    fn search_columns() -> Vec<incidents::Column> {
+        vec![incidents::Column::Title, incidents::Column::Description]
+    }
+
+    fn default_sort() -> Vec<(incidents::Column, Order)> {
+        vec![(incidents::Column::CreatedAt, Order::Desc), (incidents::Column::IncidentId, Order::Desc)]
+    }
 }
 
+type IncidentListParams = ListParams<Incidents>;
+
 // struct DroneResponse {
 //     id: String,
 //     name: String,
@@ -39,21 +81,250 @@ struct IncidentQuery {
 //     battery_level: Option<i32>,
 // }
 
+#[utoipa::path(
+    get,
+    path = "/api/incidents",
+    tag = "incidents",
+    params(PaginationParams, IncidentListParams),
+    responses((status = 200, description = "告警列表", body = ApiResponse<Page<incidents::Model>>))
+)]
 async fn get_all_incidents(
-    State(AppState { db }): State<AppState>,
-    Query(IncidentQuery { pagination }): Query<IncidentQuery>
+    State(AppState { db, .. }): State<AppState>,
+    ValidQuery(IncidentQuery { pagination, list }): ValidQuery<IncidentQuery>
 ) -> ApiResult<ApiResponse<Page<incidents::Model>>> {
-    let paginator = Incidents::find().paginate(&db, pagination.size);
+    let paginator = list.apply(Incidents::find())?.paginate(&db, pagination.size);
 
     let total = paginator.num_items().await?;
-    let incidents = paginator.fetch_page(pagination.page - 1).await?;
+    let incidents = paginator.fetch_page(pagination.index()).await?;
 
     let page = Page::from_pagination(pagination, total, incidents);
-    Ok(ApiResponse::ok("ok", Some(page)))
+    Ok(ApiResponse::ok(t!("incident.list"), Some(page)))
+}
+
+// 附近查询的最大搜索半径（米）
+const MAX_NEARBY_DISTANCE: f64 = 500_000.0;
+
+// 附近查询默认和最大返回条数
+const DEFAULT_NEARBY_LIMIT: u64 = 20;
+const MAX_NEARBY_LIMIT: u64 = 100;
+
+// 带距离的告警，distance 为到查询点的距离（米）
+#[derive(Debug, Serialize, ToSchema)]
+struct IncidentWithDistance {
+    #[serde(flatten)]
+    incident: incidents::Model,
+    distance: f64,
+}
+
+// 计算到查询点的距离，保留满足条件的告警并按距离由近到远排序
+fn rank_by_distance(
+    incidents: Vec<incidents::Model>,
+    lat: f64,
+    lng: f64,
+    keep: impl Fn(&incidents::Model, f64) -> bool
+) -> Vec<IncidentWithDistance> {
+    let mut ranked: Vec<IncidentWithDistance> = incidents
+        .into_iter()
+        .map(|incident| {
+            let distance = geo::haversine(lat, lng, geo::to_f64(&incident.lat), geo::to_f64(&incident.lng));
+            IncidentWithDistance { incident, distance }
+        })
+        .filter(|item| keep(&item.incident, item.distance))
+        .collect();
+    ranked.sort_by(|a, b| a.distance.total_cmp(&b.distance));
+    ranked
+}
+
+#[derive(Debug, Deserialize, IntoParams, Validate)]
+#[into_params(parameter_in = Query)]
+struct NearbyParams {
+    /// 中心点纬度
+    #[param(value_type = f64)]
+    #[validate(custom(function = "validation::latitude"))]
+    lat: Decimal,
+    /// 中心点经度
+    #[param(value_type = f64)]
+    #[validate(custom(function = "validation::longitude"))]
+    lng: Decimal,
+    /// 搜索半径（米）
+    #[param(maximum = 500000)]
+    #[serde(deserialize_with = "deserialize_number")]
+    #[validate(range(exclusive_min = 0.0, max = MAX_NEARBY_DISTANCE))]
+    distance: f64,
+    /// 最多返回条数
+    #[param(minimum = 1, maximum = 100, default = 20)]
+    #[serde(default, deserialize_with = "deserialize_optional_number")]
+    #[validate(range(min = 1, max = MAX_NEARBY_LIMIT))]
+    limit: Option<u64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct NearbyQuery {
+    #[serde(flatten)]
+    params: NearbyParams,
+    #[serde(flatten)]
+    list: ListQuery,
+}
+
+// 先按外接矩形在库中预筛选，再按球面距离精确过滤
+#[utoipa::path(
+    get,
+    path = "/api/incidents/nearby",
+    tag = "incidents",
+    params(NearbyParams, IncidentListParams),
+    responses((status = 200, description = "距离范围内的告警，按距离由近到远排序", body = ApiResponse<Vec<IncidentWithDistance>>))
+)]
+async fn get_nearby_incidents(
+    State(AppState { db, .. }): State<AppState>,
+    ValidQuery(NearbyQuery { params, list }): ValidQuery<NearbyQuery>
+) -> ApiResult<ApiResponse<Vec<IncidentWithDistance>>> {
+    params.validate().map_err(ApiError::Validation)?;
+
+    let (lat, lng) = (geo::to_f64(&params.lat), geo::to_f64(&params.lng));
+    let bbox = BoundingBox::around(lat, lng, params.distance);
+
+    let incidents = list
+        .apply_filters(Incidents::find())?
+        .filter(bbox.condition(incidents::Column::Lat, incidents::Column::Lng))
+        .all(&db).await?;
+
+    let mut nearby = rank_by_distance(incidents, lat, lng, |_, distance| distance <= params.distance);
+    nearby.truncate(params.limit.unwrap_or(DEFAULT_NEARBY_LIMIT) as usize);
+
+    Ok(ApiResponse::ok(t!("incident.nearby"), Some(nearby)))
+}
+
+#[derive(Debug, Deserialize, IntoParams, Validate)]
+#[serde(rename_all = "camelCase")]
+#[into_params(parameter_in = Query, rename_all = "camelCase")]
+struct BboxParams {
+    /// 南边界纬度
+    #[param(value_type = f64)]
+    #[validate(custom(function = "validation::latitude"))]
+    min_lat: Decimal,
+    /// 西边界经度，大于 maxLng 时表示跨越 180° 经线
+    #[param(value_type = f64)]
+    #[validate(custom(function = "validation::longitude"))]
+    min_lng: Decimal,
+    /// 北边界纬度
+    #[param(value_type = f64)]
+    #[validate(custom(function = "validation::latitude"))]
+    max_lat: Decimal,
+    /// 东边界经度
+    #[param(value_type = f64)]
+    #[validate(custom(function = "validation::longitude"))]
+    max_lng: Decimal,
+}
+
+#[derive(Debug, Deserialize)]
+struct BboxQuery {
+    #[serde(flatten)]
+    bbox: BboxParams,
+    #[serde(flatten)]
+    pagination: PaginationParams,
+    #[serde(flatten)]
+    list: ListQuery,
+}
+
+// 地图视野内的告警
+#[utoipa::path(
+    get,
+    path = "/api/incidents/bbox",
+    tag = "incidents",
+    params(BboxParams, PaginationParams, IncidentListParams),
+    responses((status = 200, description = "矩形区域内的告警", body = ApiResponse<Page<incidents::Model>>))
+)]
+async fn get_incidents_in_bbox(
+    State(AppState { db, .. }): State<AppState>,
+    ValidQuery(BboxQuery { bbox, pagination, list }): ValidQuery<BboxQuery>
+) -> ApiResult<ApiResponse<Page<incidents::Model>>> {
+    bbox.validate().map_err(ApiError::Validation)?;
+    if bbox.min_lat > bbox.max_lat {
+        return Err(ApiError::BadRequest(t!("geo.invalid_bbox")));
+    }
+
+    let bbox = BoundingBox {
+        min_lat: geo::to_f64(&bbox.min_lat),
+        max_lat: geo::to_f64(&bbox.max_lat),
+        min_lng: geo::to_f64(&bbox.min_lng),
+        max_lng: geo::to_f64(&bbox.max_lng),
+    };
+
+    let paginator = list
+        .apply(Incidents::find().filter(bbox.condition(incidents::Column::Lat, incidents::Column::Lng)))?
+        .paginate(&db, pagination.size);
+
+    let total = paginator.num_items().await?;
+    let incidents = paginator.fetch_page(pagination.index()).await?;
+
+    let page = Page::from_pagination(pagination, total, incidents);
+    Ok(ApiResponse::ok(t!("incident.bbox"), Some(page)))
+}
+
+#[derive(Debug, Deserialize, IntoParams, Validate)]
+#[into_params(parameter_in = Query)]
+struct PointParams {
+    /// 纬度
+    #[param(value_type = f64)]
+    #[validate(custom(function = "validation::latitude"))]
+    lat: Decimal,
+    /// 经度
+    #[param(value_type = f64)]
+    #[validate(custom(function = "validation::longitude"))]
+    lng: Decimal,
+}
+
+#[derive(Debug, Deserialize)]
+struct CoveringQuery {
+    #[serde(flatten)]
+    point: PointParams,
+    #[serde(flatten)]
+    list: ListQuery,
+}
+
+// 影响范围（radius，米）覆盖该位置的告警；以最大影响半径预筛选
+#[utoipa::path(
+    get,
+    path = "/api/incidents/covering",
+    tag = "incidents",
+    params(PointParams, IncidentListParams),
+    responses((status = 200, description = "影响范围覆盖该位置的告警，按距离由近到远排序", body = ApiResponse<Vec<IncidentWithDistance>>))
+)]
+async fn get_incidents_covering(
+    State(AppState { db, .. }): State<AppState>,
+    ValidQuery(CoveringQuery { point, list }): ValidQuery<CoveringQuery>
+) -> ApiResult<ApiResponse<Vec<IncidentWithDistance>>> {
+    point.validate().map_err(ApiError::Validation)?;
+
+    let max_radius = Incidents::find()
+        .select_only()
+        .column_as(incidents::Column::Radius.max(), "max_radius")
+        .into_tuple::<Option<f32>>()
+        .one(&db).await?
+        .flatten();
+
+    let Some(max_radius) = max_radius else {
+        return Ok(ApiResponse::ok(t!("incident.covering"), Some(Vec::new())));
+    };
+
+    let (lat, lng) = (geo::to_f64(&point.lat), geo::to_f64(&point.lng));
+    let bbox = BoundingBox::around(lat, lng, max_radius.into());
+
+    let incidents = list
+        .apply_filters(Incidents::find())?
+        .filter(incidents::Column::Radius.is_not_null())
+        .filter(bbox.condition(incidents::Column::Lat, incidents::Column::Lng))
+        .all(&db).await?;
+
+    let covering = rank_by_distance(incidents, lat, lng, |incident, distance| {
+        incident.radius.is_some_and(|radius| distance <= radius.into())
+    });
+
+    Ok(ApiResponse::ok(t!("incident.covering"), Some(covering)))
 }
 
 // async fn get_all_available_drones(
-//     State(AppState { db }): State<AppState>,
+//     State(AppState { db, .. }): State<AppState>,
 //     Query(DroneQuery { pagination }): Query<DroneQuery>
 // ) -> ApiResult<ApiResponse<Page<drones::Model>>> {
 //     let paginator = Drones::find()
@@ -61,42 +332,63 @@ async fn get_all_incidents(
 //         .paginate(&db, pagination.size);
 
 //     let total = paginator.num_items().await?;
-//     let drones = paginator.fetch_page(pagination.page - 1).await?;
+//     let drones = paginator.fetch_page(pagination.index()).await?;
 
 //     let page = Page::from_pagination(pagination, total, drones);
 
 //     Ok(ApiResponse::ok("获取所有可用无人机成功", Some(page)))
 // }
 
+#[utoipa::path(
+    get,
+    path = "/api/incidents/{id}",
+    tag = "incidents",
+    params(("id" = String, Path, description = "告警 ID")),
+    responses((status = 200, description = "告警详情", body = ApiResponse<incidents::Model>))
+)]
 async fn get_incident(
-    State(AppState { db }): State<AppState>,
-    Path(id): Path<String>
+    State(AppState { db, .. }): State<AppState>,
+    ValidPath(id): ValidPath<String>
 ) -> ApiResult<ApiResponse<incidents::Model>> {
-    let incident = Incidents::find_by_id(&id).one(&db).await.unwrap();
+    let incident = Incidents::find_by_id(&id).one(&db).await?;
     if let Some(incident) = incident {
-        Ok(ApiResponse::ok("获取事件成功", Some(incident)))
+        Ok(ApiResponse::ok(t!("incident.detail"), Some(incident)))
     } else {
-        let msg = format!("未找到ID为{}的事件", id);
-        Err(ApiError::Biz(msg))
+        let msg = t!("incident.not_found", id = id);
+        Err(ApiError::NotFound(msg))
     }
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema, Validate)]
 struct IncidentCreateRequest {
+    #[validate(length(min = 1, max = 100))]
     title: String,
     description: String,
+    #[validate(custom(function = "validation::latitude"))]
     lat: Decimal,
+    #[validate(custom(function = "validation::longitude"))]
     lng: Decimal,
+    #[validate(range(min = 0.0))]
     radius: f32,
+    #[validate(range(min = 1, max = 5))]
     severity: i8,
     status: IncidentStatus,
-    created_by: String,
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/incidents",
+    tag = "incidents",
+    request_body = IncidentCreateRequest,
+    responses((status = 200, description = "添加成功", body = MessageResponse))
+)]
 async fn add_incident(
-    State(AppState { db }): State<AppState>,
-    Json(data): Json<IncidentCreateRequest>
+    claims: Claims,
+    State(AppState { db, .. }): State<AppState>,
+    ValidJson(data): ValidJson<IncidentCreateRequest>
 ) -> ApiResult<ApiResponse<()>> {
+    let txn = db.begin().await?;
+
     let incident = IncidentsActiveModel {
         incident_id: ActiveValue::set(xid::new().to_string()),
         title: ActiveValue::set(data.title),
@@ -106,27 +398,34 @@ async fn add_incident(
         radius: ActiveValue::set(Some(data.radius)),
         severity: ActiveValue::set(Some(data.severity)),
         status: ActiveValue::set(data.status),
-        created_by: ActiveValue::set(data.created_by),
+        // 告警记在当前登录用户名下
+        created_by: ActiveValue::set(claims.user_id),
         ..Default::default()
     };
 
-    incident.insert(&db).await.unwrap();
+    let incident = incident.insert(&txn).await?;
+    record_creation(&txn, &incident).await?;
 
-    Ok(ApiResponse::ok("事件信息添加成功", None))
+    txn.commit().await?;
+    Ok(ApiResponse::ok(t!("incident.created"), None))
 }
 
 // 修改用户时的请求体
-#[derive(Deserialize)]
+#[derive(Deserialize, ToSchema, Validate)]
 struct IncidentUpdateRequest {
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[validate(length(min = 1, max = 100))]
     title: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     description: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[validate(custom(function = "validation::latitude"))]
     lat: Option<Decimal>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[validate(custom(function = "validation::longitude"))]
     lng: Option<Decimal>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[validate(range(min = 0.0))]
     radius: Option<f32>,
     #[serde(skip_serializing_if = "Option::is_none")]
     status: Option<IncidentStatus>,
@@ -141,10 +440,10 @@ impl IncidentUpdateRequest {
             incident.description = ActiveValue::set(Some(description.clone()));
         }
         if let Some(ref lat) = self.lat {
-            incident.lat = ActiveValue::set(lat.clone());
+            incident.lat = ActiveValue::set(*lat);
         }
         if let Some(ref lng) = self.lng {
-            incident.lng = ActiveValue::set(lng.clone());
+            incident.lng = ActiveValue::set(*lng);
         }
         if let Some(ref radius) = self.radius {
             incident.radius = ActiveValue::set(Some(*radius));
@@ -155,36 +454,258 @@ impl IncidentUpdateRequest {
     }
 }
 
+#[utoipa::path(
+    put,
+    path = "/api/incidents/{id}",
+    tag = "incidents",
+    params(("id" = String, Path, description = "告警 ID")),
+    request_body = IncidentUpdateRequest,
+    responses((status = 200, description = "更新成功", body = MessageResponse))
+)]
 async fn update_incident(
-    State(AppState { db }): State<AppState>,
-    Path(id): Path<String>,
-    Json(data): Json<IncidentUpdateRequest>
+    claims: Claims,
+    State(AppState { db, .. }): State<AppState>,
+    ValidPath(id): ValidPath<String>,
+    ValidJson(data): ValidJson<IncidentUpdateRequest>
 ) -> ApiResult<ApiResponse<()>> {
-    let incident = Incidents::find_by_id(&id).one(&db);
-    if let Some(incident) = incident.await.unwrap() {
-        let mut incident = incident.into_active_model();
+    let txn = db.begin().await?;
 
-        data.apply_to(&mut incident);
+    let Some(incident) = Incidents::find_by_id(&id).one(&txn).await? else {
+        return Err(ApiError::NotFound(t!("incident.not_found", id = id)));
+    };
 
-        incident.update(&db).await.unwrap();
-        Ok(ApiResponse::ok("更新事件成功", None))
-    } else {
-        Err(ApiError::Biz("事件未找到".to_string()))
-    }
+    let from = incident.status.clone();
+    let mut incident = incident.into_active_model();
+
+    data.apply_to(&mut incident);
+
+    let incident = incident.update(&txn).await?;
+    record_status_change(&txn, &id, from, incident.status, &claims.user_id).await?;
+
+    txn.commit().await?;
+    Ok(ApiResponse::ok(t!("incident.updated"), None))
 }
 
+#[utoipa::path(
+    delete,
+    path = "/api/incidents/{id}",
+    tag = "incidents",
+    params(("id" = String, Path, description = "告警 ID")),
+    responses((status = 200, description = "删除成功", body = MessageResponse))
+)]
 async fn delete_incident(
-    State(AppState { db }): State<AppState>,
-    Path(id): Path<String>
+    _: Authorized<AdminOnly>,
+    State(AppState { db, .. }): State<AppState>,
+    ValidPath(id): ValidPath<String>
 ) -> ApiResult<ApiResponse<()>> {
-    let incident = Incidents::find_by_id(&id).one(&db).await.unwrap();
+    let txn = db.begin().await?;
 
-    if let Some(incident) = incident {
-        incident.delete(&db).await.unwrap();
-        Ok(ApiResponse::ok("删除事件成功", None))
-    } else {
-        Err(ApiError::Biz("事件未找到".to_string()))
+    let Some(incident) = Incidents::find_by_id(&id).one(&txn).await? else {
+        return Err(ApiError::NotFound(t!("incident.not_found", id = id)));
+    };
+
+    // 任务保留，只解除与告警的关联；状态变更记录随告警级联删除
+    Missions::update_many()
+        .col_expr(missions::Column::IncidentId, Expr::value(Option::<String>::None))
+        .filter(missions::Column::IncidentId.eq(&id))
+        .exec(&txn).await?;
+
+    incident.delete(&txn).await?;
+
+    txn.commit().await?;
+    Ok(ApiResponse::ok(t!("incident.deleted"), None))
+}
+
+// 记录告警状态变更，状态未变化时不记录
+pub(super) async fn record_status_change<C: ConnectionTrait>(
+    conn: &C,
+    incident_id: &str,
+    from: IncidentStatus,
+    to: IncidentStatus,
+    changed_by: &str
+) -> ApiResult<()> {
+    if from == to {
+        return Ok(());
+    }
+
+    let change = IncidentStatusChangesActiveModel {
+        change_id: ActiveValue::NotSet,
+        incident_id: ActiveValue::set(incident_id.to_string()),
+        from_status: ActiveValue::set(from),
+        to_status: ActiveValue::set(to),
+        changed_by: ActiveValue::set(Some(changed_by.to_string())),
+        created_at: ActiveValue::NotSet,
+    };
+    change.insert(conn).await?;
+
+    Ok(())
+}
+
+// 记录告警的初始状态，起止状态相同的记录即时间线中的创建记录
+async fn record_creation<C: ConnectionTrait>(conn: &C, incident: &incidents::Model) -> ApiResult<()> {
+    let change = IncidentStatusChangesActiveModel {
+        change_id: ActiveValue::NotSet,
+        incident_id: ActiveValue::set(incident.incident_id.clone()),
+        from_status: ActiveValue::set(incident.status.clone()),
+        to_status: ActiveValue::set(incident.status.clone()),
+        changed_by: ActiveValue::set(Some(incident.created_by.clone())),
+        created_at: ActiveValue::NotSet,
+    };
+    change.insert(conn).await?;
+
+    Ok(())
+}
+
+// 告警时间线中的一条记录
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(tag = "type", rename_all = "camelCase")]
+enum TimelineEntry {
+    #[serde(rename_all = "camelCase")]
+    IncidentCreated {
+        at: DateTime,
+        created_by: String,
+    },
+    #[serde(rename_all = "camelCase")]
+    StatusChanged {
+        at: DateTime,
+        from: IncidentStatus,
+        to: IncidentStatus,
+        changed_by: Option<String>,
+    },
+    #[serde(rename_all = "camelCase")]
+    MissionCreated {
+        at: DateTime,
+        mission_id: String,
+        drone_id: String,
+        user_id: String,
+    },
+    #[serde(rename_all = "camelCase")]
+    MissionStarted {
+        at: DateTime,
+        mission_id: String,
+        drone_id: String,
+    },
+    #[serde(rename_all = "camelCase")]
+    MissionFinished {
+        at: DateTime,
+        mission_id: String,
+        drone_id: String,
+        status: Status,
+    },
+    #[serde(rename_all = "camelCase")]
+    MissionEvent {
+        at: DateTime,
+        mission_id: String,
+        event_id: String,
+        event_type: EventType,
+        message: Option<String>,
+    },
+}
+
+impl TimelineEntry {
+    fn at(&self) -> DateTime {
+        match self {
+            | TimelineEntry::IncidentCreated { at, .. }
+            | TimelineEntry::StatusChanged { at, .. }
+            | TimelineEntry::MissionCreated { at, .. }
+            | TimelineEntry::MissionStarted { at, .. }
+            | TimelineEntry::MissionFinished { at, .. }
+            | TimelineEntry::MissionEvent { at, .. } => *at,
+        }
+    }
+
+    fn from_change(change: incident_status_changes::Model) -> Self {
+        if change.from_status == change.to_status {
+            return TimelineEntry::IncidentCreated {
+                at: change.created_at,
+                created_by: change.changed_by.unwrap_or_default(),
+            };
+        }
+        TimelineEntry::StatusChanged {
+            at: change.created_at,
+            from: change.from_status,
+            to: change.to_status,
+            changed_by: change.changed_by,
+        }
     }
+
+    // 任务的创建、开始和结束
+    fn from_mission(mission: &missions::Model) -> Vec<Self> {
+        let mut entries = vec![TimelineEntry::MissionCreated {
+            at: mission.created_at,
+            mission_id: mission.mission_id.clone(),
+            drone_id: mission.drone_id.clone(),
+            user_id: mission.user_id.clone(),
+        }];
+        if let Some(at) = mission.started_at {
+            entries.push(TimelineEntry::MissionStarted {
+                at,
+                mission_id: mission.mission_id.clone(),
+                drone_id: mission.drone_id.clone(),
+            });
+        }
+        if let Some(at) = mission.completed_at {
+            entries.push(TimelineEntry::MissionFinished {
+                at,
+                mission_id: mission.mission_id.clone(),
+                drone_id: mission.drone_id.clone(),
+                status: mission.status.clone(),
+            });
+        }
+        entries
+    }
+}
+
+// 合并告警的创建和状态变更、关联的任务及其事件，按时间先后排列
+#[utoipa::path(
+    get,
+    path = "/api/incidents/{id}/timeline",
+    tag = "incidents",
+    params(("id" = String, Path, description = "告警 ID")),
+    responses((status = 200, description = "告警时间线", body = ApiResponse<Vec<TimelineEntry>>))
+)]
+async fn get_incident_timeline(
+    State(AppState { db, .. }): State<AppState>,
+    ValidPath(id): ValidPath<String>
+) -> ApiResult<ApiResponse<Vec<TimelineEntry>>> {
+    let Some(incident) = Incidents::find_by_id(&id).one(&db).await? else {
+        return Err(ApiError::NotFound(t!("incident.not_found", id = id)));
+    };
+
+    let changes = incident
+        .find_related(IncidentStatusChanges)
+        .order_by_asc(incident_status_changes::Column::ChangeId)
+        .all(&db).await?;
+    let missions = incident
+        .find_related(Missions)
+        .order_by_asc(missions::Column::CreatedAt)
+        .all(&db).await?;
+    let events = missions.load_many(Events, &db).await?;
+
+    // 早于初始状态记录创建的告警没有这条记录，退回使用告警的创建时间
+    let recorded = changes.iter().any(|change| change.from_status == change.to_status);
+    let mut timeline = Vec::new();
+    if !recorded && let Some(at) = incident.created_at {
+        timeline.push(TimelineEntry::IncidentCreated { at, created_by: incident.created_by });
+    }
+    timeline.extend(changes.into_iter().map(TimelineEntry::from_change));
+    for (mission, events) in missions.iter().zip(events) {
+        timeline.extend(TimelineEntry::from_mission(mission));
+        timeline.extend(
+            events.into_iter().map(|event| TimelineEntry::MissionEvent {
+                at: event.created_at,
+                mission_id: event.mission_id,
+                event_id: event.event_id,
+                event_type: event.event_type,
+                message: event.message,
+            })
+        );
+    }
+
+    // 稳定排序，同一时刻的记录保持生成顺序
+    timeline.sort_by_key(TimelineEntry::at);
+
+    Ok(ApiResponse::ok(t!("incident.timeline"), Some(timeline)))
 }
 
 // #[derive(Debug, Serialize, FromQueryResult)]
@@ -199,7 +720,7 @@ async fn delete_incident(
 // }
 
 // async fn get_incident_status(State(
-//     AppState { db },
+//     AppState { db, .. },
 // ): State<AppState>) -> ApiResult<ApiResponse<IncidentStatusResponse>> {
 //     let result = Incidents::find()
 //         .select_only()
@@ -212,11 +733,32 @@ async fn delete_incident(
 //     Ok(ApiResponse::ok("获取事件状态分布成功", Some(IncidentStatusResponse { categories: result })))
 // }
 
+#[derive(OpenApi)]
+#[openapi(
+    paths(
+        get_all_incidents,
+        get_nearby_incidents,
+        get_incidents_in_bbox,
+        get_incidents_covering,
+        get_incident_timeline,
+        add_incident,
+        get_incident,
+        update_incident,
+        delete_incident
+    ),
+    tags((name = "incidents", description = "告警事件"))
+)]
+pub struct IncidentApi;
+
 pub fn create_incident_router() -> Router<AppState> {
     Router::new()
         .route("/", get(get_all_incidents))
         .route("/", post(add_incident))
+        .route("/nearby", get(get_nearby_incidents))
+        .route("/bbox", get(get_incidents_in_bbox))
+        .route("/covering", get(get_incidents_covering))
         .route("/{id}", get(get_incident))
+        .route("/{id}/timeline", get(get_incident_timeline))
         .route("/{id}", put(update_incident))
         .route("/{id}", delete(delete_incident))
     // .route("/available", get(get_all_available_incidents))
@@ -0,0 +1,250 @@
+use std::collections::HashSet;
+
+use axum::extract::ws::{ Message, WebSocket, WebSocketUpgrade };
+use axum::extract::State;
+use axum::http::HeaderMap;
+use axum::response::Response;
+use axum::Router;
+use axum::routing::get;
+use serde::{ Deserialize, Deserializer };
+use utoipa::{ IntoParams, OpenApi };
+use tokio::sync::broadcast::error::RecvError;
+
+use crate::app::AppState;
+use crate::common::{ auth, i18n };
+use crate::common::jwt::{ self, Claims };
+use crate::common::query::parse_variant;
+use crate::common::result::{ ApiError, ApiResult };
+use crate::common::validation::ValidQuery;
+use crate::entity::sea_orm_active_enums::EventType;
+use crate::hub::HubMessage;
+use crate::t;
+
+// 订阅过滤条件，未设置的条件不做限制；事件类型只作用于事件消息
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SubscriptionFilter {
+    drone_ids: Option<HashSet<String>>,
+    mission_ids: Option<HashSet<String>>,
+    #[serde(default, deserialize_with = "deserialize_event_types")]
+    event_types: Option<Vec<EventType>>,
+}
+
+// 事件类型与事件列表接口一致，使用数据库中的取值，例如 takeoff、battery_low
+fn parse_event_type(name: &str) -> Option<EventType> {
+    parse_variant::<EventType>(name)
+}
+
+fn deserialize_event_types<'de, D>(deserializer: D) -> Result<Option<Vec<EventType>>, D::Error>
+    where D: Deserializer<'de>
+{
+    let Some(names) = Option::<Vec<String>>::deserialize(deserializer)? else {
+        return Ok(None);
+    };
+    names
+        .iter()
+        .map(|name| {
+            parse_event_type(name).ok_or_else(|| {
+                serde::de::Error::custom(t!("event.unknown_type", name = name))
+            })
+        })
+        .collect::<Result<Vec<_>, _>>()
+        .map(Some)
+}
+
+impl SubscriptionFilter {
+    fn matches(&self, message: &HubMessage) -> bool {
+        fn contains(set: &Option<HashSet<String>>, value: Option<&str>) -> bool {
+            match set {
+                Some(set) => value.is_some_and(|value| set.contains(value)),
+                None => true,
+            }
+        }
+
+        if !contains(&self.drone_ids, message.drone_id()) {
+            return false;
+        }
+        if !contains(&self.mission_ids, message.mission_id()) {
+            return false;
+        }
+
+        match (&self.event_types, message.event_type()) {
+            (Some(types), Some(event_type)) => types.contains(event_type),
+            _ => true,
+        }
+    }
+}
+
+// 浏览器无法为 WebSocket 设置请求头，因此令牌和初始过滤条件都可通过查询参数传递，多个值以逗号分隔
+#[derive(Debug, Deserialize, IntoParams)]
+#[serde(rename_all = "camelCase")]
+#[into_params(parameter_in = Query)]
+struct LiveQuery {
+    token: Option<String>,
+    drone_ids: Option<String>,
+    mission_ids: Option<String>,
+    event_types: Option<String>,
+}
+
+fn split(value: Option<String>) -> Option<HashSet<String>> {
+    value.map(|value| {
+        value
+            .split(',')
+            .map(str::trim)
+            .filter(|item| !item.is_empty())
+            .map(String::from)
+            .collect()
+    })
+}
+
+impl LiveQuery {
+    fn into_filter(self) -> ApiResult<SubscriptionFilter> {
+        let event_types = match split(self.event_types) {
+            Some(types) =>
+                Some(
+                    types
+                        .into_iter()
+                        .map(|name| {
+                            parse_event_type(&name).ok_or_else(|| {
+                                ApiError::BadRequest(t!("event.unknown_type", name = name))
+                            })
+                        })
+                        .collect::<ApiResult<Vec<_>>>()?
+                ),
+            None => None,
+        };
+
+        Ok(SubscriptionFilter {
+            drone_ids: split(self.drone_ids),
+            mission_ids: split(self.mission_ids),
+            event_types,
+        })
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/ws",
+    tag = "live",
+    security(()),
+    params(LiveQuery),
+    responses((status = 101, description = "升级为 WebSocket，推送遥测、任务状态和事件消息；客户端可发送 JSON 过滤条件替换订阅。处理过慢时推送 lagged 消息告知丢弃条数，令牌过期后推送 expired 消息并关闭连接"))
+)]
+async fn live_feed(
+    State(AppState { config, hub, .. }): State<AppState>,
+    headers: HeaderMap,
+    ValidQuery(query): ValidQuery<LiveQuery>,
+    ws: WebSocketUpgrade
+) -> ApiResult<Response> {
+    let token = auth::bearer_token(&headers).map(String::from).or(query.token.clone());
+
+    let Some(token) = token else {
+        return Err(ApiError::Unauthorized(t!("auth.token_missing")));
+    };
+    let claims = jwt
+        ::decode_token(&config.jwt, &token)
+        .map_err(|_| ApiError::Unauthorized(t!("auth.token_invalid")))?;
+
+    let filter = query.into_filter()?;
+    let receiver = hub.subscribe();
+
+    tracing::info!("用户<{}>订阅实时推送", claims.user_id);
+
+    // 升级后的连接在请求上下文之外运行，沿用握手请求的语言
+    let locale = i18n::current();
+    Ok(ws.on_upgrade(move |socket| i18n::with_locale(locale, handle_socket(socket, receiver, filter, claims))))
+}
+
+// 普通用户只能看到自己任务的状态变化和事件，与任务、事件列表接口的可见范围一致
+fn visible_to(claims: &Claims, message: &HubMessage) -> bool {
+    match message {
+        HubMessage::MissionStatus(change) => claims.is_self_or_admin(&change.user_id),
+        HubMessage::Event(created) => claims.is_self_or_admin(&created.user_id),
+        _ => true,
+    }
+}
+
+async fn send_json(socket: &mut WebSocket, value: serde_json::Value) -> bool {
+    socket.send(Message::Text(value.to_string().into())).await.is_ok()
+}
+
+async fn handle_socket(
+    mut socket: WebSocket,
+    mut receiver: tokio::sync::broadcast::Receiver<HubMessage>,
+    mut filter: SubscriptionFilter,
+    claims: Claims
+) {
+    // 令牌过期后关闭连接，客户端需使用新令牌重新订阅
+    let expiry = tokio::time::sleep(claims.remaining());
+    tokio::pin!(expiry);
+
+    loop {
+        tokio::select! {
+            _ = &mut expiry => {
+                let _ = send_json(
+                    &mut socket,
+                    serde_json::json!({ "type": "expired", "message": t!("auth.token_invalid") })
+                ).await;
+                let _ = socket.send(Message::Close(None)).await;
+                break;
+            }
+            message = receiver.recv() => {
+                let message = match message {
+                    Ok(message) => message,
+                    // 处理过慢时告知客户端丢失的消息数，客户端可通过查询接口补齐
+                    Err(RecvError::Lagged(skipped)) => {
+                        tracing::warn!("实时推送订阅者处理过慢，丢弃{}条消息", skipped);
+                        let notice = serde_json::json!({ "type": "lagged", "data": { "skipped": skipped } });
+                        if !send_json(&mut socket, notice).await {
+                            break;
+                        }
+                        continue;
+                    }
+                    Err(RecvError::Closed) => break,
+                };
+
+                if !visible_to(&claims, &message) || !filter.matches(&message) {
+                    continue;
+                }
+
+                let Ok(text) = serde_json::to_string(&message) else {
+                    continue;
+                };
+                if socket.send(Message::Text(text.into())).await.is_err() {
+                    break;
+                }
+            }
+            incoming = socket.recv() => {
+                match incoming {
+                    // 客户端发送新的过滤条件（JSON）以替换当前订阅
+                    Some(Ok(Message::Text(text))) => {
+                        match serde_json::from_str::<SubscriptionFilter>(&text) {
+                            Ok(new_filter) => filter = new_filter,
+                            Err(e) => {
+                                let reply = serde_json::json!({ "type": "error", "message": e.to_string() });
+                                if !send_json(&mut socket, reply).await {
+                                    break;
+                                }
+                            }
+                        }
+                    }
+                    Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                    Some(Ok(_)) => {}
+                }
+            }
+        }
+    }
+}
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(
+        live_feed
+    ),
+    tags((name = "live", description = "实时推送"))
+)]
+pub struct LiveApi;
+
+pub fn create_live_router() -> Router<AppState> {
+    Router::new().route("/", get(live_feed))
+}
@@ -1,30 +1,282 @@
+use std::collections::BTreeSet;
+use std::convert::Infallible;
+
 use axum::Router;
-use axum::extract::{ Query, State };
+use axum::extract::State;
+use axum::http::HeaderMap;
+use axum::response::sse::{ Event, KeepAlive, Sse };
 use axum::routing::{ get };
+use tokio::sync::broadcast::error::RecvError;
+use tokio::sync::{ broadcast, mpsc };
+use tokio_stream::wrappers::ReceiverStream;
+use tokio_stream::{ Stream, StreamExt };
 
 use sea_orm::entity::prelude::*;
-use sea_orm::ActiveValue;
+use sea_orm::{ ActiveValue, Order, QueryOrder, QuerySelect };
+use serde::Deserialize;
+use utoipa::{ IntoParams, OpenApi };
 use crate::app::AppState;
-use crate::common::page::{ Page, PaginationParams };
+use crate::common::auth::{ AdminOnly, Authorized };
+use crate::common::cursor::{ self, Cursor, CursorId, Keyset };
+use crate::common::page::{ CursorParams, Listing, Page, PaginationParams };
+use crate::common::query::{ parse_enum, FieldKind, ListParams, ListQuery, QueryField, Queryable };
 use crate::common::response::ApiResponse;
-use crate::common::result::ApiResult;
+use crate::common::result::{ ApiError, ApiResult };
+use crate::common::validation::ValidQuery;
 use crate::entity::logs;
 use crate::entity::prelude::Logs;
 use crate::entity::logs::ActiveModel as LogsActiveModel;
 use crate::entity::sea_orm_active_enums::LogType;
+use crate::hub::Hub;
+use crate::t;
+
+#[derive(Deserialize)]
+struct LogQuery {
+    #[serde(flatten)]
+    pagination: PaginationParams,
+    #[serde(flatten)]
+    cursor: CursorParams,
+    #[serde(flatten)]
+    list: ListQuery,
+}
+
+impl Queryable for Logs {
+    fn fields() -> Vec<QueryField<logs::Column>> {
+        vec![
+            QueryField::new("log_type", logs::Column::LogType, FieldKind::Enum(parse_enum::<LogType>)),
+            QueryField::new("created_at", logs::Column::CreatedAt, FieldKind::DateTime)
+        ]
+    }
+
+    fn search_columns() -> Vec<logs::Column> {
+        vec![logs::Column::Message]
+    }
+
+    fn default_sort() -> Vec<(logs::Column, Order)> {
+        vec![(logs::Column::LogId, Order::Desc)]
+    }
+}
+
+type LogListParams = ListParams<Logs>;
+
+// 日志的 created_at 可为空，游标只按自增主键遍历，顺序与写入顺序一致
+impl Keyset for Logs {
+    fn time_column() -> Option<logs::Column> {
+        None
+    }
 
+    fn id_column() -> logs::Column {
+        logs::Column::LogId
+    }
+
+    fn cursor_of(model: &logs::Model) -> Cursor {
+        Cursor { time: None, id: CursorId::Int(model.log_id.into()) }
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/logs",
+    tag = "logs",
+    params(PaginationParams, CursorParams, LogListParams),
+    responses((status = 200, description = "日志列表，带 cursor 或 limit 时使用游标分页", body = ApiResponse<Listing<logs::Model>>))
+)]
 async fn get_all_logs(
-    State(AppState { db }): State<AppState>,
-    Query(pagination): Query<PaginationParams>
-) -> ApiResult<ApiResponse<Page<logs::Model>>> {
-    let paginator = Logs::find().paginate(&db, pagination.size);
+    _: Authorized<AdminOnly>,
+    State(AppState { db, .. }): State<AppState>,
+    ValidQuery(LogQuery { pagination, cursor, list }): ValidQuery<LogQuery>
+) -> ApiResult<ApiResponse<Listing<logs::Model>>> {
+    if cursor.is_active() {
+        let select = list.apply_for_cursor(Logs::find())?;
+        let page = cursor::fetch(&db, select, &cursor).await?;
+        return Ok(ApiResponse::ok(t!("log.list"), Some(Listing::Cursor(page))));
+    }
+
+    let paginator = list.apply(Logs::find())?.paginate(&db, pagination.size);
 
     let total = paginator.num_items().await?;
-    let logs = paginator.fetch_page(pagination.page - 1).await?;
+    let logs = paginator.fetch_page(pagination.index()).await?;
 
     let page = Page::from_pagination(pagination, total, logs);
 
-    Ok(ApiResponse::ok("ok", Some(page)))
+    Ok(ApiResponse::ok(t!("log.list"), Some(Listing::Page(page))))
+}
+
+// 补发历史日志时每次查询的条数
+const RESUME_PAGE_SIZE: u64 = 500;
+
+// 用于去重的最近已推送日志 ID 数量
+const RECENT_IDS: usize = 4096;
+
+// 已推送的日志 ID：不大于 floor 的视为已推送，其上保留最近的 ID 用于去重。
+// 并发写入的日志可能乱序广播，因此不能只记录最大 ID
+struct SentIds {
+    floor: i32,
+    ids: BTreeSet<i32>,
+}
+
+impl SentIds {
+    fn new(floor: i32) -> Self {
+        SentIds { floor, ids: BTreeSet::new() }
+    }
+
+    // 未推送过时记录并返回 true
+    fn insert(&mut self, id: i32) -> bool {
+        if id <= self.floor || !self.ids.insert(id) {
+            return false;
+        }
+        while self.ids.len() > RECENT_IDS {
+            if let Some(oldest) = self.ids.pop_first() {
+                self.floor = oldest;
+            }
+        }
+        true
+    }
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[serde(rename_all = "camelCase")]
+#[into_params(parameter_in = Query)]
+struct LogStreamQuery {
+    /// 逗号分隔的日志类型，如 WARN,ERROR
+    log_type: Option<String>,
+}
+
+fn parse_log_types(value: Option<String>) -> ApiResult<Option<Vec<LogType>>> {
+    let Some(value) = value else {
+        return Ok(None);
+    };
+
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .map(|name| {
+            LogType::try_from_value(&name.to_uppercase()).map_err(|_|
+                ApiError::BadRequest(t!("log.unknown_type", name = name))
+            )
+        })
+        .collect::<ApiResult<Vec<_>>>()
+        .map(Some)
+}
+
+fn to_sse_event(log: &logs::Model) -> Event {
+    Event::default()
+        .id(log.log_id.to_string())
+        .event("log")
+        .json_data(log)
+        .unwrap_or_default()
+}
+
+// 实时推送新日志；携带 Last-Event-ID 重连时先补发该 ID 之后的日志
+#[utoipa::path(
+    get,
+    path = "/api/logs/stream",
+    tag = "logs",
+    params(LogStreamQuery, ("Last-Event-ID" = Option<i32>, Header, description = "断线重连时补发该 ID 之后的日志")),
+    responses((status = 200, description = "SSE 日志流，事件名为 log，data 为日志 JSON", content_type = "text/event-stream", body = logs::Model))
+)]
+async fn stream_logs(
+    _: Authorized<AdminOnly>,
+    State(AppState { db, hub, .. }): State<AppState>,
+    headers: HeaderMap,
+    ValidQuery(LogStreamQuery { log_type }): ValidQuery<LogStreamQuery>
+) -> ApiResult<Sse<impl Stream<Item = Result<Event, Infallible>>>> {
+    let log_types = parse_log_types(log_type)?;
+    let last_event_id = headers
+        .get("Last-Event-ID")
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.trim().parse::<i32>().ok());
+
+    // 不续传时只推送连接之后的新日志；先读当前最大 ID 再订阅，
+    // 两者之间写入的日志由补发流程从数据库取回，不会被当作旧日志丢弃
+    let floor = match last_event_id {
+        Some(last_event_id) => last_event_id,
+        None =>
+            Logs::find()
+                .select_only()
+                .column_as(logs::Column::LogId.max(), "log_id")
+                .into_tuple::<Option<i32>>()
+                .one(&db).await?
+                .flatten()
+                .unwrap_or_default(),
+    };
+
+    // 先订阅再补发历史，避免补发期间产生的日志丢失
+    let receiver = hub.subscribe_logs();
+
+    let (sender, stream) = mpsc::channel(RESUME_PAGE_SIZE as usize);
+    tokio::spawn(forward_logs(db, receiver, log_types, SentIds::new(floor), sender));
+
+    let stream = ReceiverStream::new(stream).map(|log| Ok(to_sse_event(&log)));
+    Ok(Sse::new(stream).keep_alive(KeepAlive::default()))
+}
+
+// 分页补发 floor 之后尚未推送的日志，直到追上最新写入；客户端断开时返回 false
+async fn catch_up(
+    db: &DatabaseConnection,
+    log_types: &Option<Vec<LogType>>,
+    sent: &mut SentIds,
+    sender: &mpsc::Sender<logs::Model>
+) -> ApiResult<bool> {
+    let mut after = sent.floor;
+    loop {
+        let mut select = Logs::find()
+            .filter(logs::Column::LogId.gt(after))
+            .order_by_asc(logs::Column::LogId)
+            .limit(RESUME_PAGE_SIZE);
+        if let Some(log_types) = log_types {
+            select = select.filter(logs::Column::LogType.is_in(log_types.clone()));
+        }
+        let page = select.all(db).await?;
+        let done = (page.len() as u64) < RESUME_PAGE_SIZE;
+
+        for log in page {
+            after = log.log_id;
+            if sent.insert(log.log_id) && sender.send(log).await.is_err() {
+                return Ok(false);
+            }
+        }
+        if done {
+            return Ok(true);
+        }
+    }
+}
+
+// 先补发历史日志，再转发新日志；落后于广播通道时回到数据库补齐
+async fn forward_logs(
+    db: DatabaseConnection,
+    mut receiver: broadcast::Receiver<logs::Model>,
+    log_types: Option<Vec<LogType>>,
+    mut sent: SentIds,
+    sender: mpsc::Sender<logs::Model>
+) {
+    let mut resume = true;
+    loop {
+        if resume {
+            match catch_up(&db, &log_types, &mut sent, &sender).await {
+                Ok(true) => resume = false,
+                Ok(false) => return,
+                Err(e) => {
+                    tracing::error!("补发日志失败: {}", e);
+                    return;
+                }
+            }
+        }
+
+        match receiver.recv().await {
+            Ok(log) => {
+                if let Some(ref log_types) = log_types && !log_types.contains(&log.log_type) {
+                    continue;
+                }
+                if sent.insert(log.log_id) && sender.send(log).await.is_err() {
+                    return;
+                }
+            }
+            Err(RecvError::Lagged(_)) => resume = true,
+            Err(RecvError::Closed) => return,
+        }
+    }
 }
 
 pub struct LogManager;
@@ -32,6 +284,7 @@ pub struct LogManager;
 impl LogManager {
     pub async fn create_log(
         db: &DatabaseConnection,
+        hub: &Hub,
         log_type: LogType,
         message: String
     ) -> ApiResult<()> {
@@ -42,7 +295,9 @@ impl LogManager {
             created_at: ActiveValue::NotSet,
         };
 
-        log.insert(db).await?;
+        let log = log.insert(db).await?;
+
+        hub.publish_log(log);
         Ok(())
     }
 
@@ -59,6 +314,18 @@ impl LogManager {
     }
 }
 
+#[derive(OpenApi)]
+#[openapi(
+    paths(
+        get_all_logs,
+        stream_logs
+    ),
+    tags((name = "logs", description = "系统日志"))
+)]
+pub struct LogApi;
+
 pub fn create_logs_router() -> Router<AppState> {
-    Router::new().route("/", get(get_all_logs))
+    Router::new()
+        .route("/", get(get_all_logs))
+        .route("/stream", get(stream_logs))
 }
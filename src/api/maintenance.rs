@@ -0,0 +1,244 @@
+use axum::extract::State;
+use axum::Router;
+use axum::routing::{ get, post };
+use sea_orm::entity::prelude::*;
+use sea_orm::{ ActiveValue, IntoActiveModel, QueryOrder, TransactionTrait };
+use serde::{ Deserialize, Serialize };
+use utoipa::{ OpenApi, ToSchema };
+use validator::Validate;
+
+use crate::api::logs::LogManager;
+use crate::api::mission::{ lock_drone, ACTIVE_STATUSES };
+use crate::app::AppState;
+use crate::common::auth::{ AdminOnly, Authorized };
+use crate::common::page::{ Page, PaginationParams };
+use crate::common::response::ApiResponse;
+use crate::common::result::{ ApiError, ApiResult };
+use crate::common::time;
+use crate::common::validation::{ ValidJson, ValidPath, ValidQuery };
+use crate::entity::maintenance_records::{
+    ActiveModel as MaintenanceRecordsActiveModel,
+    Part,
+    Parts,
+};
+use crate::entity::prelude::{ Drones, MaintenanceRecords, Missions };
+use crate::entity::sea_orm_active_enums::{ MaintenanceType, Status };
+use crate::entity::{ maintenance_records, missions };
+use crate::t;
+
+#[derive(Debug, Serialize, Deserialize, ToSchema, Validate)]
+struct PartRequest {
+    #[validate(length(min = 1, max = 50))]
+    name: String,
+    #[validate(range(min = 1))]
+    quantity: u32,
+}
+
+fn to_parts(parts: Option<Vec<PartRequest>>) -> Option<Parts> {
+    parts.map(|parts| {
+        Parts(
+            parts
+                .into_iter()
+                .map(|part| Part { name: part.name, quantity: part.quantity })
+                .collect()
+        )
+    })
+}
+
+#[derive(Debug, Deserialize, ToSchema, Validate)]
+#[serde(rename_all = "camelCase")]
+struct MaintenanceStartRequest {
+    maintenance_type: MaintenanceType,
+    #[validate(length(min = 1, max = 50))]
+    technician: String,
+    #[validate(length(max = 2000))]
+    notes: Option<String>,
+    /// 更换的零件，最多 100 种
+    #[validate(length(max = 100), nested)]
+    parts: Option<Vec<PartRequest>>,
+}
+
+// 结束维护时无需补充信息传空对象即可
+#[derive(Debug, Deserialize, ToSchema, Validate)]
+#[serde(rename_all = "camelCase")]
+struct MaintenanceCompleteRequest {
+    /// 不为空时覆盖开始维护时填写的备注
+    #[validate(length(max = 2000))]
+    notes: Option<String>,
+    /// 不为空时覆盖开始维护时登记的零件
+    #[validate(length(max = 100), nested)]
+    parts: Option<Vec<PartRequest>>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/drones/{id}/maintenance",
+    tag = "maintenance",
+    params(("id" = String, Path, description = "无人机 ID"), PaginationParams),
+    responses((status = 200, description = "维护记录，最近的在前", body = ApiResponse<Page<maintenance_records::Model>>))
+)]
+async fn get_maintenance_records(
+    State(AppState { db, .. }): State<AppState>,
+    ValidPath(id): ValidPath<String>,
+    ValidQuery(pagination): ValidQuery<PaginationParams>
+) -> ApiResult<ApiResponse<Page<maintenance_records::Model>>> {
+    if Drones::find_by_id(&id).one(&db).await?.is_none() {
+        return Err(ApiError::NotFound(t!("drone.not_found", id = id)));
+    }
+
+    let paginator = MaintenanceRecords::find()
+        .filter(maintenance_records::Column::DroneId.eq(&id))
+        .order_by_desc(maintenance_records::Column::StartedAt)
+        .order_by_desc(maintenance_records::Column::RecordId)
+        .paginate(&db, pagination.size);
+
+    let total = paginator.num_items().await?;
+    let records = paginator.fetch_page(pagination.index()).await?;
+
+    let page = Page::from_pagination(pagination, total, records);
+    Ok(ApiResponse::ok(t!("maintenance.list"), Some(page)))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/drones/{id}/maintenance",
+    tag = "maintenance",
+    params(("id" = String, Path, description = "无人机 ID")),
+    request_body = MaintenanceStartRequest,
+    responses(
+        (status = 200, description = "无人机进入维护状态，不再分配任务", body = ApiResponse<maintenance_records::Model>),
+        (status = 409, description = "无人机已在维护中或正在执行任务")
+    )
+)]
+async fn start_maintenance(
+    Authorized { claims, .. }: Authorized<AdminOnly>,
+    State(AppState { db, hub, .. }): State<AppState>,
+    ValidPath(id): ValidPath<String>,
+    ValidJson(data): ValidJson<MaintenanceStartRequest>
+) -> ApiResult<ApiResponse<maintenance_records::Model>> {
+    let txn = db.begin().await?;
+
+    let drone = lock_drone(&txn, &id).await?;
+    if drone.status == Status::Maintenance {
+        return Err(ApiError::Conflict(t!("maintenance.already", name = drone.name)));
+    }
+
+    let active = Missions::find()
+        .filter(missions::Column::DroneId.eq(&id))
+        .filter(missions::Column::Status.is_in(ACTIVE_STATUSES))
+        .count(&txn).await?;
+    if active > 0 {
+        return Err(ApiError::Conflict(t!("drone.busy", name = drone.name)));
+    }
+
+    let name = drone.name.clone();
+    let mut drone = drone.into_active_model();
+    drone.status = ActiveValue::set(Status::Maintenance);
+    drone.update(&txn).await?;
+
+    let record = (MaintenanceRecordsActiveModel {
+        record_id: ActiveValue::NotSet,
+        drone_id: ActiveValue::set(id.clone()),
+        maintenance_type: ActiveValue::set(data.maintenance_type),
+        technician: ActiveValue::set(data.technician),
+        notes: ActiveValue::set(data.notes),
+        parts: ActiveValue::set(to_parts(data.parts)),
+        started_at: ActiveValue::set(time::now()),
+        ended_at: ActiveValue::set(None),
+        created_by: ActiveValue::set(Some(claims.user_id.clone())),
+    }).insert(&txn).await?;
+
+    txn.commit().await?;
+
+    LogManager::create_log(
+        &db,
+        &hub,
+        LogManager::info(),
+        format!("用户<{}>将无人机<{}>转入维护，维护记录<{}>", claims.user_id, name, record.record_id)
+    ).await?;
+
+    Ok(ApiResponse::ok(t!("maintenance.started"), Some(record)))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/drones/{id}/maintenance/complete",
+    tag = "maintenance",
+    params(("id" = String, Path, description = "无人机 ID")),
+    request_body = MaintenanceCompleteRequest,
+    responses(
+        (status = 200, description = "维护结束，无人机恢复空闲", body = ApiResponse<maintenance_records::Model>),
+        (status = 409, description = "无人机不在维护中")
+    )
+)]
+async fn complete_maintenance(
+    Authorized { claims, .. }: Authorized<AdminOnly>,
+    State(AppState { db, hub, .. }): State<AppState>,
+    ValidPath(id): ValidPath<String>,
+    ValidJson(data): ValidJson<MaintenanceCompleteRequest>
+) -> ApiResult<ApiResponse<maintenance_records::Model>> {
+    let txn = db.begin().await?;
+
+    let drone = lock_drone(&txn, &id).await?;
+    if drone.status != Status::Maintenance {
+        return Err(ApiError::Conflict(t!("maintenance.not_in_maintenance", name = drone.name)));
+    }
+
+    let Some(record) = MaintenanceRecords::find()
+        .filter(maintenance_records::Column::DroneId.eq(&id))
+        .filter(maintenance_records::Column::EndedAt.is_null())
+        .order_by_desc(maintenance_records::Column::StartedAt)
+        .one(&txn).await? else {
+        return Err(ApiError::Conflict(t!("maintenance.not_in_maintenance", name = drone.name)));
+    };
+
+    let name = drone.name.clone();
+    let mut drone = drone.into_active_model();
+    drone.status = ActiveValue::set(Status::Idle);
+    drone.update(&txn).await?;
+
+    let mut record = record.into_active_model();
+    record.ended_at = ActiveValue::set(Some(time::now()));
+    if let Some(notes) = data.notes {
+        record.notes = ActiveValue::set(Some(notes));
+    }
+    if data.parts.is_some() {
+        record.parts = ActiveValue::set(to_parts(data.parts));
+    }
+    let record = record.update(&txn).await?;
+
+    txn.commit().await?;
+
+    LogManager::create_log(
+        &db,
+        &hub,
+        LogManager::info(),
+        format!("用户<{}>结束无人机<{}>的维护，维护记录<{}>", claims.user_id, name, record.record_id)
+    ).await?;
+
+    Ok(ApiResponse::ok(t!("maintenance.completed"), Some(record)))
+}
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(get_maintenance_records, start_maintenance, complete_maintenance),
+    components(
+        schemas(
+            maintenance_records::Model,
+            Part,
+            Parts,
+            PartRequest,
+            MaintenanceType,
+            MaintenanceStartRequest,
+            MaintenanceCompleteRequest
+        )
+    ),
+    tags((name = "maintenance", description = "无人机维护"))
+)]
+pub struct MaintenanceApi;
+
+pub fn create_maintenance_router() -> Router<AppState> {
+    Router::new()
+        .route("/{id}/maintenance", get(get_maintenance_records).post(start_maintenance))
+        .route("/{id}/maintenance/complete", post(complete_maintenance))
+}
@@ -1,30 +1,62 @@
-use axum::extract::{ Json, Path, Query, State };
+use axum::extract::State;
 use axum::Router;
 
 use axum::routing::{ delete, get, post, put };
 
 use serde::{ Deserialize, Serialize };
+use utoipa::{ OpenApi, ToSchema };
+use validator::Validate;
 
+use crate::api::geofence;
+use crate::api::waypoint::load_waypoints;
 use crate::app::AppState;
+use crate::common::auth;
+use crate::common::jwt::Claims;
+use crate::common::time;
+use crate::hub::{ Hub, HubMessage, MissionStatusChange };
 
 use crate::common::page::{ Page, PaginationParams };
-use crate::common::response::ApiResponse;
+use crate::common::query::{ parse_enum, FieldKind, ListParams, ListQuery, QueryField, Queryable };
+use crate::common::response::{ ApiResponse, MessageResponse };
 use crate::common::result::{ ApiError, ApiResult };
-use crate::entity::prelude::Missions;
+use crate::common::validation::{ self, ValidJson, ValidPath, ValidQuery };
+use crate::entity::prelude::{ Drones, Missions };
 use crate::entity::sea_orm_active_enums::Status;
-use crate::entity::missions;
+use crate::entity::{ drones, missions };
 use crate::entity::missions::ActiveModel as MissionsActiveModel;
 use sea_orm::entity::prelude::*;
 use sea_orm::{
     ActiveModelTrait,
     ActiveValue,
+    Condition,
     EntityTrait,
     IntoActiveModel,
     ModelTrait,
     PaginatorTrait,
     FromQueryResult,
     QuerySelect,
+    TransactionTrait,
+    Order,
 };
+use crate::t;
+
+// 执行中的任务状态，处于这些状态的任务会占用无人机
+pub(super) const ACTIVE_STATUSES: [Status; 2] = [Status::Working, Status::Returning];
+
+// 占用无人机的任务：执行中的任务，以及派遣或模板生成后尚未开始的任务
+pub(super) fn reserving_missions() -> Condition {
+    Condition::any()
+        .add(missions::Column::Status.is_in(ACTIVE_STATUSES))
+        .add(
+            Condition::all()
+                .add(missions::Column::Status.eq(Status::Idle))
+                .add(
+                    Condition::any()
+                        .add(missions::Column::IncidentId.is_not_null())
+                        .add(missions::Column::TemplateId.is_not_null())
+                )
+        )
+}
 
 // use Decimal;
 
@@ -41,82 +73,421 @@ use sea_orm::{
 //     completed_at: Option<DateTimeUtc>,
 // }
 
-// 修改任务时的请求体
 #[derive(Deserialize)]
+struct MissionQuery {
+    #[serde(flatten)]
+    pagination: PaginationParams,
+    #[serde(flatten)]
+    list: ListQuery,
+}
+
+impl Queryable for Missions {
+    fn fields() -> Vec<QueryField<missions::Column>> {
+        vec![
+            QueryField::new("user_id", missions::Column::UserId, FieldKind::Text),
+            QueryField::new("drone_id", missions::Column::DroneId, FieldKind::Text),
+            QueryField::new("incident_id", missions::Column::IncidentId, FieldKind::Text),
+            QueryField::new("template_id", missions::Column::TemplateId, FieldKind::Text),
+            QueryField::new("status", missions::Column::Status, FieldKind::Enum(parse_enum::<Status>)),
+            QueryField::new("created_at", missions::Column::CreatedAt, FieldKind::DateTime),
+            QueryField::new("started_at", missions::Column::StartedAt, FieldKind::DateTime),
+            QueryField::new("completed_at", missions::Column::CompletedAt, FieldKind::DateTime)
+        ]
+    }
+
+    fn default_sort() -> Vec<(missions::Column, Order)> {
+        vec![(missions::Column::CreatedAt, Order::Desc), (missions::Column::MissionId, Order::Desc)]
+    }
+}
+
+type MissionListParams = ListParams<Missions>;
+
+// 修改任务时的请求体
+#[derive(Deserialize, ToSchema, Validate)]
 pub struct MissionUpdateRequest {
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[validate(custom(function = "validation::latitude"))]
     target_lat: Option<Decimal>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[validate(custom(function = "validation::longitude"))]
     target_lng: Option<Decimal>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    status: Option<Status>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    started_at: Option<DateTime>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    completed_at: Option<DateTime>,
 }
 
 impl MissionUpdateRequest {
     fn apply_to(&self, mission: &mut MissionsActiveModel) {
         if let Some(ref target_lat) = self.target_lat {
-            mission.target_lat = ActiveValue::set(Some(target_lat.clone()));
+            mission.target_lat = ActiveValue::set(Some(*target_lat));
         }
         if let Some(ref target_lng) = self.target_lng {
-            mission.target_lng = ActiveValue::set(Some(target_lng.clone()));
+            mission.target_lng = ActiveValue::set(Some(*target_lng));
+        }
+    }
+}
+
+// 任务状态流转：idle -> working -> returning -> completed，未结束的任务可随时标记为 error
+#[derive(Debug, Clone, Copy)]
+enum MissionAction {
+    Start,
+    Return,
+    Complete,
+    Fail,
+}
+
+impl MissionAction {
+    fn name(self) -> &'static str {
+        match self {
+            MissionAction::Start => "开始",
+            MissionAction::Return => "返航",
+            MissionAction::Complete => "完成",
+            MissionAction::Fail => "标记失败",
+        }
+    }
+
+    // 返回给客户端的动作名称
+    fn label(self) -> String {
+        match self {
+            MissionAction::Start => t!("mission.action.start"),
+            MissionAction::Return => t!("mission.action.return"),
+            MissionAction::Complete => t!("mission.action.complete"),
+            MissionAction::Fail => t!("mission.action.fail"),
+        }
+    }
+
+    fn target(self) -> Status {
+        match self {
+            MissionAction::Start => Status::Working,
+            MissionAction::Return => Status::Returning,
+            MissionAction::Complete => Status::Completed,
+            MissionAction::Fail => Status::Error,
         }
-        if let Some(ref status) = self.status {
-            mission.status = ActiveValue::set(status.clone());
+    }
+
+    fn allowed_from(self, status: &Status) -> bool {
+        match self {
+            MissionAction::Start => *status == Status::Idle,
+            MissionAction::Return => *status == Status::Working,
+            MissionAction::Complete => matches!(status, Status::Working | Status::Returning),
+            MissionAction::Fail =>
+                matches!(status, Status::Idle | Status::Working | Status::Returning),
         }
+    }
 
-        if let Some(ref started_at) = self.started_at {
-            mission.started_at = ActiveValue::set(Some(started_at.clone()));
+    // 任务流转后无人机应处的状态
+    fn drone_status(self) -> Status {
+        match self {
+            MissionAction::Start => Status::Working,
+            MissionAction::Return => Status::Returning,
+            MissionAction::Complete => Status::Idle,
+            MissionAction::Fail => Status::Error,
         }
-        if let Some(ref completed_at) = self.completed_at {
-            mission.completed_at = ActiveValue::set(Some(completed_at.clone()));
+    }
+
+    fn apply_to(self, mission: &mut MissionsActiveModel) {
+        let now = time::now();
+
+        mission.status = ActiveValue::set(self.target());
+        match self {
+            MissionAction::Start => {
+                mission.started_at = ActiveValue::set(Some(now));
+            }
+            MissionAction::Complete | MissionAction::Fail => {
+                mission.completed_at = ActiveValue::set(Some(now));
+            }
+            MissionAction::Return => {}
         }
     }
 }
 
+// 在事务中锁住无人机行，之后对无人机状态的检查和更新不会与其他任务流转或维护操作交错
+pub(super) async fn lock_drone<C: ConnectionTrait>(conn: &C, id: &str) -> ApiResult<drones::Model> {
+    Drones::find_by_id(id)
+        .lock_exclusive()
+        .one(conn).await?
+        .ok_or_else(|| ApiError::NotFound(t!("drone.not_found", id = id)))
+}
+
+// 检查无人机是否可以执行新任务：已激活、未处于异常或维护状态，且没有执行中的任务，
+// 也没有被派遣或模板生成的待执行任务占用
+pub(super) async fn ensure_drone_available<C: ConnectionTrait>(
+    conn: &C,
+    drone_id: &str,
+    exclude_mission: Option<&str>
+) -> ApiResult<drones::Model> {
+    let Some(drone) = Drones::find_by_id(drone_id).lock_exclusive().one(conn).await? else {
+        return Err(ApiError::InvalidReference(t!("drone.not_found", id = drone_id)));
+    };
+
+    if drone.activate == 0 {
+        return Err(ApiError::Conflict(t!("drone.inactive", name = drone.name)));
+    }
+    if drone.status == Status::Error {
+        return Err(ApiError::Conflict(t!("drone.faulty", name = drone.name)));
+    }
+    if drone.status == Status::Maintenance {
+        return Err(ApiError::Conflict(t!("drone.in_maintenance", name = drone.name)));
+    }
+    if ACTIVE_STATUSES.contains(&drone.status) {
+        return Err(ApiError::Conflict(t!("drone.busy", name = drone.name)));
+    }
+
+    let mut reserving = Missions::find()
+        .filter(missions::Column::DroneId.eq(drone_id))
+        .filter(reserving_missions());
+    if let Some(mission_id) = exclude_mission {
+        reserving = reserving.filter(missions::Column::MissionId.ne(mission_id));
+    }
+
+    let reserving = reserving.all(conn).await?;
+    if reserving.iter().any(|mission| ACTIVE_STATUSES.contains(&mission.status)) {
+        return Err(ApiError::Conflict(t!("drone.busy", name = drone.name)));
+    }
+    if !reserving.is_empty() {
+        return Err(ApiError::Conflict(t!("drone.reserved", name = drone.name)));
+    }
+
+    Ok(drone)
+}
+
+// 在同一事务中更新任务状态和所属无人机状态
+async fn transition_mission(
+    db: &DatabaseConnection,
+    hub: &Hub,
+    claims: &Claims,
+    id: &str,
+    action: MissionAction
+) -> ApiResult<missions::Model> {
+    let txn = db.begin().await?;
+
+    let Some(mission) = Missions::find_by_id(id).lock_exclusive().one(&txn).await? else {
+        return Err(ApiError::NotFound(t!("mission.not_found", id = id)));
+    };
+
+    if !claims.is_self_or_admin(&mission.user_id) {
+        return Err(auth::deny(db, hub, claims, &format!("{}任务<{}>", action.name(), id)).await);
+    }
+
+    if !action.allowed_from(&mission.status) {
+        return Err(
+            ApiError::Conflict(
+                t!(
+                    "mission.invalid_transition",
+                    id = id,
+                    status = format!("{:?}", mission.status),
+                    action = action.label(),
+                    target = format!("{:?}", action.target())
+                )
+            )
+        );
+    }
+
+    // 先锁住无人机行再检查占用情况，避免并发开始的任务同时通过检查
+    let drone = match action {
+        MissionAction::Start => ensure_drone_available(&txn, &mission.drone_id, Some(id)).await?,
+        _ => lock_drone(&txn, &mission.drone_id).await?,
+    };
+
+    // 起飞前检查目标点和航线是否进入禁飞区
+    if let MissionAction::Start = action {
+        let waypoints = load_waypoints(&txn, id).await?;
+        let target = mission.target_lat.zip(mission.target_lng);
+        geofence::check_route(&txn, target, &waypoints).await?.ensure_clear()?;
+    }
+
+    let from = mission.status.clone();
+
+    let mut mission = mission.into_active_model();
+    action.apply_to(&mut mission);
+    let mission = mission.update(&txn).await?;
+
+    // 只有开始执行或结束无人机当前执行的任务时才同步无人机状态，
+    // 取消尚未开始的任务不影响正在执行其他任务的无人机
+    if matches!(action, MissionAction::Start) || ACTIVE_STATUSES.contains(&from) {
+        let mut drone = drone.into_active_model();
+        drone.status = ActiveValue::set(action.drone_status());
+        drone.update(&txn).await?;
+    }
+
+    txn.commit().await?;
+
+    hub.publish(
+        HubMessage::MissionStatus(MissionStatusChange {
+            mission_id: mission.mission_id.clone(),
+            drone_id: mission.drone_id.clone(),
+            user_id: mission.user_id.clone(),
+            from,
+            to: mission.status.clone(),
+            changed_at: time::now(),
+        })
+    );
+
+    Ok(mission)
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/missions/{id}/start",
+    tag = "missions",
+    params(("id" = String, Path, description = "任务 ID")),
+    responses(
+        (status = 200, description = "任务开始执行", body = ApiResponse<missions::Model>),
+        (status = 409, description = "当前状态不允许该操作或无人机被占用，或航线进入禁飞区")
+    )
+)]
+async fn start_mission(
+    claims: Claims,
+    State(AppState { db, hub, .. }): State<AppState>,
+    ValidPath(id): ValidPath<String>
+) -> ApiResult<ApiResponse<missions::Model>> {
+    let mission = transition_mission(&db, &hub, &claims, &id, MissionAction::Start).await?;
+    Ok(ApiResponse::ok(t!("mission.started"), Some(mission)))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/missions/{id}/return",
+    tag = "missions",
+    params(("id" = String, Path, description = "任务 ID")),
+    responses(
+        (status = 200, description = "无人机开始返航", body = ApiResponse<missions::Model>),
+        (status = 409, description = "当前状态不允许该操作或无人机被占用")
+    )
+)]
+async fn return_mission(
+    claims: Claims,
+    State(AppState { db, hub, .. }): State<AppState>,
+    ValidPath(id): ValidPath<String>
+) -> ApiResult<ApiResponse<missions::Model>> {
+    let mission = transition_mission(&db, &hub, &claims, &id, MissionAction::Return).await?;
+    Ok(ApiResponse::ok(t!("mission.returning"), Some(mission)))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/missions/{id}/complete",
+    tag = "missions",
+    params(("id" = String, Path, description = "任务 ID")),
+    responses(
+        (status = 200, description = "任务完成", body = ApiResponse<missions::Model>),
+        (status = 409, description = "当前状态不允许该操作或无人机被占用")
+    )
+)]
+async fn complete_mission(
+    claims: Claims,
+    State(AppState { db, hub, .. }): State<AppState>,
+    ValidPath(id): ValidPath<String>
+) -> ApiResult<ApiResponse<missions::Model>> {
+    let mission = transition_mission(&db, &hub, &claims, &id, MissionAction::Complete).await?;
+    Ok(ApiResponse::ok(t!("mission.completed"), Some(mission)))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/missions/{id}/fail",
+    tag = "missions",
+    params(("id" = String, Path, description = "任务 ID")),
+    responses(
+        (status = 200, description = "任务标记为失败", body = ApiResponse<missions::Model>),
+        (status = 409, description = "当前状态不允许该操作或无人机被占用")
+    )
+)]
+async fn fail_mission(
+    claims: Claims,
+    State(AppState { db, hub, .. }): State<AppState>,
+    ValidPath(id): ValidPath<String>
+) -> ApiResult<ApiResponse<missions::Model>> {
+    let mission = transition_mission(&db, &hub, &claims, &id, MissionAction::Fail).await?;
+    Ok(ApiResponse::ok(t!("mission.failed"), Some(mission)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/missions",
+    tag = "missions",
+    params(PaginationParams, MissionListParams),
+    responses((status = 200, description = "任务列表，普通用户只返回自己的任务", body = ApiResponse<Page<missions::Model>>))
+)]
 async fn get_all_mission(
-    State(AppState { db }): State<AppState>,
-    Query(pagination): Query<PaginationParams>
+    claims: Claims,
+    State(AppState { db, .. }): State<AppState>,
+    ValidQuery(MissionQuery { pagination, list }): ValidQuery<MissionQuery>
 ) -> ApiResult<ApiResponse<Page<missions::Model>>> {
-    let paginator = Missions::find().paginate(&db, pagination.size);
+    let mut select = list.apply(Missions::find())?;
+
+    // 普通用户只能查看自己发起的任务
+    if !claims.is_admin() {
+        select = select.filter(missions::Column::UserId.eq(&claims.user_id));
+    }
+
+    let paginator = select.paginate(&db, pagination.size);
 
     let total = paginator.num_items().await?;
-    let missions = paginator.fetch_page(pagination.page - 1).await?;
+    let missions = paginator.fetch_page(pagination.index()).await?;
 
     let page = Page::from_pagination(pagination, total, missions);
 
-    Ok(ApiResponse::ok("ok", Some(page)))
+    Ok(ApiResponse::ok(t!("mission.list"), Some(page)))
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/missions/{id}",
+    tag = "missions",
+    params(("id" = String, Path, description = "任务 ID")),
+    responses((status = 200, description = "任务详情", body = ApiResponse<missions::Model>))
+)]
 async fn get_mission(
-    State(AppState { db }): State<AppState>,
-    Path(id): Path<String>
+    claims: Claims,
+    State(AppState { db, hub, .. }): State<AppState>,
+    ValidPath(id): ValidPath<String>
 ) -> ApiResult<ApiResponse<missions::Model>> {
-    let mission = Missions::find_by_id(&id).one(&db).await.unwrap();
+    let mission = Missions::find_by_id(&id).one(&db).await?;
 
     if let Some(mission) = mission {
-        Ok(ApiResponse::ok("ok", Some(mission)))
+        if !claims.is_self_or_admin(&mission.user_id) {
+            return Err(auth::deny(&db, &hub, &claims, &format!("查看任务<{}>", id)).await);
+        }
+        Ok(ApiResponse::ok(t!("mission.detail"), Some(mission)))
     } else {
-        let msg = format!("任务ID<{}>未找到", id);
-        Err(ApiError::Biz(msg))
+        let msg = t!("mission.not_found", id = id);
+        Err(ApiError::NotFound(msg))
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema, Validate)]
 struct MissionCreateRequest {
+    #[validate(length(min = 1, max = 32))]
     user_id: String,
+    #[validate(length(min = 1, max = 32))]
     drone_id: String,
+    #[validate(custom(function = "validation::latitude"))]
     target_lat: Decimal,
+    #[validate(custom(function = "validation::longitude"))]
     target_lng: Decimal,
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/missions",
+    tag = "missions",
+    request_body = MissionCreateRequest,
+    responses(
+        (status = 200, description = "创建成功", body = MessageResponse),
+        (status = 409, description = "无人机被占用（包括派遣或模板生成的待执行任务）或目标点位于禁飞区")
+    )
+)]
 async fn add_mission(
-    State(AppState { db }): State<AppState>,
-    Json(data): Json<MissionCreateRequest>
+    claims: Claims,
+    State(AppState { db, hub, .. }): State<AppState>,
+    ValidJson(data): ValidJson<MissionCreateRequest>
 ) -> ApiResult<ApiResponse<()>> {
+    if !claims.is_self_or_admin(&data.user_id) {
+        return Err(auth::deny(&db, &hub, &claims, "为其他用户创建任务").await);
+    }
+
+    let txn = db.begin().await?;
+    ensure_drone_available(&txn, &data.drone_id, None).await?;
+    geofence::check_route(&txn, Some((data.target_lat, data.target_lng)), &[]).await?.ensure_clear()?;
+
     let mission = MissionsActiveModel {
         mission_id: ActiveValue::set(xid::new().to_string()),
         user_id: ActiveValue::set(data.user_id),
@@ -127,59 +498,119 @@ async fn add_mission(
         ..Default::default()
     };
 
-    mission.insert(&db).await.unwrap();
+    mission.insert(&txn).await?;
+    txn.commit().await?;
 
-    Ok(ApiResponse::ok("创建任务成功", None))
+    Ok(ApiResponse::ok(t!("mission.created"), None))
 }
 
+#[utoipa::path(
+    delete,
+    path = "/api/missions/{id}",
+    tag = "missions",
+    params(("id" = String, Path, description = "任务 ID")),
+    responses(
+        (status = 200, description = "删除成功", body = MessageResponse),
+        (status = 409, description = "任务正在执行")
+    )
+)]
 async fn delete_mission(
-    State(AppState { db }): State<AppState>,
-    Path(id): Path<String>
+    claims: Claims,
+    State(AppState { db, hub, .. }): State<AppState>,
+    ValidPath(id): ValidPath<String>
 ) -> ApiResult<ApiResponse<()>> {
-    let data = Missions::find_by_id(&id).one(&db).await.unwrap();
+    let txn = db.begin().await?;
+    let data = Missions::find_by_id(&id).lock_exclusive().one(&txn).await?;
 
     if let Some(data) = data {
-        data.delete(&db).await.unwrap();
-        Ok(ApiResponse::ok("删除任务成功", None))
+        if !claims.is_self_or_admin(&data.user_id) {
+            return Err(auth::deny(&db, &hub, &claims, &format!("删除任务<{}>", id)).await);
+        }
+        // 执行中的任务占用着无人机，需先完成或标记失败
+        if ACTIVE_STATUSES.contains(&data.status) {
+            return Err(ApiError::Conflict(t!("mission.delete_active", id = id)));
+        }
+        data.delete(&txn).await?;
+        txn.commit().await?;
+        Ok(ApiResponse::ok(t!("mission.deleted"), None))
     } else {
-        let msg = format!("未找到该任务");
-        Err(ApiError::Biz(msg))
+        let msg = t!("mission.not_found", id = id);
+        Err(ApiError::NotFound(msg))
     }
 }
 
+#[utoipa::path(
+    put,
+    path = "/api/missions/{id}",
+    tag = "missions",
+    params(("id" = String, Path, description = "任务 ID")),
+    request_body = MissionUpdateRequest,
+    responses(
+        (status = 200, description = "更新成功", body = MessageResponse),
+        (status = 409, description = "任务已开始或已结束，或目标点位于禁飞区")
+    )
+)]
 async fn update_mission(
-    State(AppState { db }): State<AppState>,
-    Path(id): Path<String>,
-    Json(data): Json<MissionUpdateRequest>
+    claims: Claims,
+    State(AppState { db, hub, .. }): State<AppState>,
+    ValidPath(id): ValidPath<String>,
+    ValidJson(data): ValidJson<MissionUpdateRequest>
 ) -> ApiResult<ApiResponse<()>> {
-    let mission = Missions::find_by_id(id).one(&db).await.unwrap();
+    // 锁住任务行，避免修改目标点时任务被并发开始
+    let txn = db.begin().await?;
+    let mission = Missions::find_by_id(&id).lock_exclusive().one(&txn).await?;
 
     if let Some(mission) = mission {
+        if !claims.is_self_or_admin(&mission.user_id) {
+            return Err(auth::deny(&db, &hub, &claims, &format!("修改任务<{}>", id)).await);
+        }
+        // 与航点一致，只有未开始的任务可以修改目标点
+        if mission.status != Status::Idle {
+            return Err(
+                ApiError::Conflict(
+                    t!("mission.locked", id = id, status = format!("{:?}", mission.status))
+                )
+            );
+        }
+        if data.target_lat.is_some() || data.target_lng.is_some() {
+            let target = data.target_lat
+                .or(mission.target_lat)
+                .zip(data.target_lng.or(mission.target_lng));
+            geofence::check_route(&txn, target, &[]).await?.ensure_clear()?;
+        }
+
         let mut mission = mission.into_active_model();
 
         data.apply_to(&mut mission);
 
-        mission.update(&db).await.unwrap();
-        Ok(ApiResponse::ok("更新任务成功", None))
+        mission.update(&txn).await?;
+        txn.commit().await?;
+        Ok(ApiResponse::ok(t!("mission.updated"), None))
     } else {
-        let msg = format!("未找到该任务");
-        Err(ApiError::Biz(msg))
+        let msg = t!("mission.not_found", id = id);
+        Err(ApiError::NotFound(msg))
     }
 }
 
-#[derive(Debug, Serialize, FromQueryResult)]
+#[derive(Debug, Serialize, FromQueryResult, ToSchema)]
 struct MissionStatusItem {
     status: String,
     count: i64,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 struct MissionStatusResponse {
     categories: Vec<MissionStatusItem>,
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/missions/status",
+    tag = "missions",
+    responses((status = 200, description = "按状态统计的任务数量", body = ApiResponse<MissionStatusResponse>))
+)]
 async fn get_mission_status(State(
-    AppState { db },
+    AppState { db, .. },
 ): State<AppState>) -> ApiResult<ApiResponse<MissionStatusResponse>> {
     let result = Missions::find()
         .select_only()
@@ -189,9 +620,27 @@ async fn get_mission_status(State(
         .into_model::<MissionStatusItem>()
         .all(&db).await?;
 
-    Ok(ApiResponse::ok("获取任务状态分布成功", Some(MissionStatusResponse { categories: result })))
+    Ok(ApiResponse::ok(t!("mission.status_summary"), Some(MissionStatusResponse { categories: result })))
 }
 
+#[derive(OpenApi)]
+#[openapi(
+    paths(
+        get_all_mission,
+        add_mission,
+        get_mission,
+        update_mission,
+        delete_mission,
+        start_mission,
+        return_mission,
+        complete_mission,
+        fail_mission,
+        get_mission_status
+    ),
+    tags((name = "missions", description = "任务管理与状态流转"))
+)]
+pub struct MissionApi;
+
 pub fn create_mission_router() -> Router<AppState> {
     Router::new()
         .route("/", get(get_all_mission))
@@ -199,5 +648,42 @@ pub fn create_mission_router() -> Router<AppState> {
         .route("/{id}", get(get_mission))
         .route("/{id}", put(update_mission))
         .route("/{id}", delete(delete_mission))
+        .route("/{id}/start", post(start_mission))
+        .route("/{id}/return", post(return_mission))
+        .route("/{id}/complete", post(complete_mission))
+        .route("/{id}/fail", post(fail_mission))
         .route("/status", get(get_mission_status))
 }
+
+#[cfg(test)]
+mod tests {
+    use sea_orm::Iterable;
+
+    use super::*;
+
+    fn allowed(action: MissionAction) -> Vec<Status> {
+        Status::iter().filter(|status| action.allowed_from(status)).collect()
+    }
+
+    #[test]
+    fn follows_the_mission_lifecycle() {
+        assert_eq!(allowed(MissionAction::Start), [Status::Idle]);
+        assert_eq!(allowed(MissionAction::Return), [Status::Working]);
+        assert_eq!(allowed(MissionAction::Complete), [Status::Working, Status::Returning]);
+    }
+
+    #[test]
+    fn fails_only_unfinished_missions() {
+        assert_eq!(allowed(MissionAction::Fail), [Status::Idle, Status::Working, Status::Returning]);
+        assert!(!MissionAction::Fail.allowed_from(&Status::Completed));
+        assert!(!MissionAction::Fail.allowed_from(&Status::Error));
+    }
+
+    #[test]
+    fn targets_reach_the_next_state() {
+        // 每个动作的目标状态不能再次执行同一动作，避免重复推进
+        for action in [MissionAction::Start, MissionAction::Return, MissionAction::Complete, MissionAction::Fail] {
+            assert!(!action.allowed_from(&action.target()), "{:?}", action);
+        }
+    }
+}
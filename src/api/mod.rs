@@ -1,39 +1,92 @@
+mod auth;
 mod user;
 mod drone;
 mod mission;
-mod logs;
+pub mod logs;
 mod events;
 mod incident;
+mod telemetry;
+mod live;
+mod docs;
+mod dispatch;
+mod waypoint;
+mod geofence;
+pub mod template;
+mod maintenance;
+mod usage;
 
+use crate::api::auth::create_auth_router;
 use crate::api::drone::create_drone_router;
 use crate::api::events::create_event_router;
 use crate::api::logs::create_logs_router;
 use crate::api::mission::create_mission_router;
 use crate::api::user::create_user_router;
 use crate::api::incident::create_incident_router;
+use crate::api::telemetry::create_telemetry_router;
+use crate::api::live::create_live_router;
+use crate::api::docs::create_docs_router;
+use crate::api::dispatch::create_dispatch_router;
+use crate::api::waypoint::create_waypoint_router;
+use crate::api::geofence::{ create_geofence_router, create_preflight_router };
+use crate::api::template::create_template_router;
+use crate::api::maintenance::create_maintenance_router;
+use crate::api::usage::create_usage_router;
 use crate::app::AppState;
-use crate::common::result::{ ApiError, ApiResult };
+use crate::common::AppConfig;
+use crate::common::auth::JwtAuth;
+use crate::common::i18n;
+use crate::common::result::{ self, ApiError, ApiResult };
 use axum::Router;
+use axum::middleware;
 
-use axum::http::Method;
-use tower_http::cors::{ Any, CorsLayer };
+use axum::http::{ HeaderValue, Method };
+use tower_http::cors::{ AllowOrigin, Any, CorsLayer };
+use tower_http::validate_request::ValidateRequestHeaderLayer;
+use crate::t;
 
-pub fn create_overall_router() -> Router<AppState> {
+fn allow_origin(config: &AppConfig) -> AllowOrigin {
+    if config.cors.allow_any() {
+        return AllowOrigin::from(Any);
+    }
+
+    // 配置加载时已校验过来源格式
+    AllowOrigin::list(
+        config.cors.origins.iter().filter_map(|origin| HeaderValue::from_str(origin).ok())
+    )
+}
+
+pub fn create_overall_router(config: &AppConfig) -> Router<AppState> {
     let cors = CorsLayer::new()
         .allow_methods([Method::GET, Method::POST, Method::PUT, Method::DELETE])
-        .allow_origin(Any)
+        .allow_origin(allow_origin(config))
         .allow_headers(Any);
 
-    Router::new().nest(
-        "/api",
-        Router::new()
-            .nest("/users", create_user_router())
-            .nest("/drones", create_drone_router())
-            .nest("/missions", create_mission_router())
-            .nest("/logs", create_logs_router())
-            .nest("/events", create_event_router())
-            .nest("/incidents", create_incident_router())
-            .fallback(async || -> ApiResult<()> { Err(ApiError::NotFound) })
-            .layer(cors)
-    )
+    // 需要携带访问令牌才能访问的路由
+    let protected = Router::new()
+        .nest("/users", create_user_router())
+        .nest("/drones", create_drone_router().merge(create_maintenance_router()).merge(create_usage_router()))
+        .nest(
+            "/missions",
+            create_mission_router().merge(create_waypoint_router()).merge(create_preflight_router())
+        )
+        .nest("/mission-templates", create_template_router())
+        .nest("/geofences", create_geofence_router())
+        .nest("/logs", create_logs_router())
+        .nest("/events", create_event_router())
+        .nest("/incidents", create_incident_router().merge(create_dispatch_router()))
+        .nest("/telemetry", create_telemetry_router())
+        .layer(ValidateRequestHeaderLayer::custom(JwtAuth::new(config.jwt.clone())));
+
+    let mut api = Router::new()
+        .nest("/auth", create_auth_router())
+        .nest("/ws", create_live_router())
+        .merge(create_docs_router())
+        .merge(protected)
+        .fallback(async || -> ApiResult<()> { Err(ApiError::NotFound(t!("route.not_found"))) });
+
+    if config.api.legacy_errors {
+        api = api.layer(middleware::map_response(result::legacy_envelope));
+    }
+
+    Router::new().nest("/api", api.layer(middleware::from_fn(i18n::localize)).layer(cors))
 }
@@ -0,0 +1,342 @@
+use std::collections::{ BTreeSet, HashMap };
+
+use axum::extract::State;
+use axum::Router;
+use axum::routing::{ get, post };
+
+use sea_orm::entity::prelude::*;
+use sea_orm::{ ActiveValue, IntoActiveModel, Order, TransactionTrait };
+use serde::{ Deserialize, Serialize };
+use utoipa::{ OpenApi, ToSchema };
+use validator::Validate;
+
+use crate::app::AppState;
+use crate::common::auth;
+use crate::common::jwt::Claims;
+use crate::common::cursor::{ self, Cursor, CursorId, Keyset };
+use crate::common::page::{ CursorParams, Listing, Page, PaginationParams };
+use crate::common::query::{ FieldKind, ListParams, ListQuery, QueryField, Queryable };
+use crate::common::response::ApiResponse;
+use crate::common::result::{ ApiError, ApiResult };
+use crate::common::validation::{ self, ValidJson, ValidQuery };
+use crate::common::time;
+use crate::entity::prelude::{ Drones, Missions, Telemetry };
+use crate::entity::{ drones, missions, telemetry };
+use crate::entity::telemetry::ActiveModel as TelemetryActiveModel;
+use crate::hub::{ HubMessage, TelemetryUpdate, HUB_CAPACITY };
+use crate::t;
+
+// 单次批量上报的最大条数
+const MAX_BATCH_SIZE: u64 = 5000;
+
+// 一次批量上报的每条数据都会推送给实时订阅者，广播通道必须能容纳整批消息
+const _: () = assert!(MAX_BATCH_SIZE as usize <= HUB_CAPACITY);
+
+// 单条 INSERT 语句写入的条数，避免超出数据库的参数个数上限
+const INSERT_CHUNK_SIZE: usize = 500;
+
+#[derive(Debug, Serialize, Deserialize, ToSchema, Validate)]
+#[serde(rename_all = "camelCase")]
+struct TelemetryRecord {
+    #[validate(length(min = 1, max = 32))]
+    drone_id: String,
+    #[validate(length(max = 32))]
+    mission_id: Option<String>,
+    #[validate(custom(function = "validation::latitude"))]
+    lat: Decimal,
+    #[validate(custom(function = "validation::longitude"))]
+    lng: Decimal,
+    altitude: f32,
+    #[validate(range(min = 0.0, max = 360.0))]
+    heading: Option<f32>,
+    #[validate(range(min = 0.0))]
+    speed: Option<f32>,
+    #[validate(range(max = 100))]
+    battery: u8,
+    // 缺省时使用服务端接收时间
+    recorded_at: Option<DateTime>,
+}
+
+impl TelemetryRecord {
+    fn to_update(&self, recorded_at: DateTime) -> TelemetryUpdate {
+        TelemetryUpdate {
+            drone_id: self.drone_id.clone(),
+            mission_id: self.mission_id.clone(),
+            lat: self.lat,
+            lng: self.lng,
+            altitude: self.altitude,
+            heading: self.heading,
+            speed: self.speed,
+            battery: self.battery,
+            recorded_at,
+        }
+    }
+
+    fn into_active_model(self, recorded_at: DateTime) -> TelemetryActiveModel {
+        TelemetryActiveModel {
+            telemetry_id: ActiveValue::NotSet,
+            drone_id: ActiveValue::set(self.drone_id),
+            mission_id: ActiveValue::set(self.mission_id),
+            lat: ActiveValue::set(self.lat),
+            lng: ActiveValue::set(self.lng),
+            altitude: ActiveValue::set(self.altitude),
+            heading: ActiveValue::set(self.heading),
+            speed: ActiveValue::set(self.speed),
+            battery: ActiveValue::set(self.battery),
+            recorded_at: ActiveValue::set(recorded_at),
+            created_at: ActiveValue::NotSet,
+        }
+    }
+}
+
+// 批量上报遥测数据时的请求体
+#[derive(Debug, Deserialize, ToSchema, Validate)]
+struct TelemetryBatchRequest {
+    #[validate(length(min = 1, max = MAX_BATCH_SIZE), nested)]
+    records: Vec<TelemetryRecord>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+struct TelemetryBatchResponse {
+    accepted: usize,
+    drones: usize,
+}
+
+// 无人机在本批数据中的最新状态
+struct LatestState {
+    lat: Decimal,
+    lng: Decimal,
+    altitude: f32,
+    battery: u8,
+    recorded_at: DateTime,
+}
+
+impl LatestState {
+    fn apply_to(&self, drone: &mut drones::ActiveModel) {
+        drone.last_known_lat = ActiveValue::set(Some(self.lat));
+        drone.last_known_lng = ActiveValue::set(Some(self.lng));
+        drone.last_known_altitude = ActiveValue::set(Some(self.altitude));
+        drone.battery = ActiveValue::set(self.battery);
+        drone.last_seen_at = ActiveValue::set(Some(self.recorded_at));
+    }
+}
+
+// 普通用户只能为自己任务的无人机上报遥测：每条数据都需带上所属用户的任务，且无人机与任务一致
+async fn owns_records(
+    db: &DatabaseConnection,
+    claims: &Claims,
+    records: &[TelemetryRecord]
+) -> ApiResult<bool> {
+    let Some(mission_ids) = records
+        .iter()
+        .map(|record| record.mission_id.clone())
+        .collect::<Option<BTreeSet<String>>>() else {
+        return Ok(false);
+    };
+
+    let missions: HashMap<String, String> = Missions::find()
+        .filter(missions::Column::MissionId.is_in(mission_ids))
+        .filter(missions::Column::UserId.eq(&claims.user_id))
+        .all(db).await?
+        .into_iter()
+        .map(|mission| (mission.mission_id, mission.drone_id))
+        .collect();
+
+    Ok(
+        records.iter().all(|record| {
+            record.mission_id
+                .as_ref()
+                .and_then(|mission_id| missions.get(mission_id))
+                .is_some_and(|drone_id| *drone_id == record.drone_id)
+        })
+    )
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/telemetry/batch",
+    tag = "telemetry",
+    request_body = TelemetryBatchRequest,
+    responses(
+        (status = 200, description = "上报成功", body = ApiResponse<TelemetryBatchResponse>),
+        (status = 403, description = "普通用户上报了不属于自己任务的遥测")
+    )
+)]
+async fn ingest_telemetry(
+    claims: Claims,
+    State(AppState { db, hub, .. }): State<AppState>,
+    ValidJson(data): ValidJson<TelemetryBatchRequest>
+) -> ApiResult<ApiResponse<TelemetryBatchResponse>> {
+    let drone_ids: BTreeSet<String> = data.records
+        .iter()
+        .map(|record| record.drone_id.clone())
+        .collect();
+
+    if !claims.is_admin() && !owns_records(&db, &claims, &data.records).await? {
+        return Err(auth::deny(&db, &hub, &claims, "为其他用户的任务上报遥测").await);
+    }
+
+    let txn = db.begin().await?;
+
+    let known = Drones::find()
+        .filter(drones::Column::DroneId.is_in(drone_ids.iter().cloned()))
+        .all(&txn).await?;
+
+    if known.len() != drone_ids.len() {
+        let missing = drone_ids
+            .iter()
+            .find(|id| !known.iter().any(|drone| &drone.drone_id == *id))
+            .cloned()
+            .unwrap_or_default();
+        return Err(ApiError::InvalidReference(t!("drone.not_found", id = missing)));
+    }
+
+    let now = time::now();
+    let accepted = data.records.len();
+    let mut latest: HashMap<String, LatestState> = HashMap::new();
+    let mut batch = Vec::with_capacity(INSERT_CHUNK_SIZE);
+    let mut updates = Vec::with_capacity(accepted);
+
+    for record in data.records {
+        let recorded_at = record.recorded_at.unwrap_or(now);
+
+        let is_newer = latest
+            .get(&record.drone_id)
+            .is_none_or(|state| state.recorded_at <= recorded_at);
+        if is_newer {
+            latest.insert(record.drone_id.clone(), LatestState {
+                lat: record.lat,
+                lng: record.lng,
+                altitude: record.altitude,
+                battery: record.battery,
+                recorded_at,
+            });
+        }
+
+        updates.push(record.to_update(recorded_at));
+        batch.push(record.into_active_model(recorded_at));
+        if batch.len() == INSERT_CHUNK_SIZE {
+            Telemetry::insert_many(std::mem::take(&mut batch)).exec(&txn).await?;
+        }
+    }
+    if !batch.is_empty() {
+        Telemetry::insert_many(batch).exec(&txn).await?;
+    }
+
+    // 只有比库中更新的数据才会刷新无人机的最新状态，乱序到达的旧数据只入库
+    for drone in known {
+        let Some(state) = latest.get(&drone.drone_id) else {
+            continue;
+        };
+        if drone.last_seen_at.is_some_and(|seen| seen > state.recorded_at) {
+            continue;
+        }
+
+        let mut drone = drone.into_active_model();
+        state.apply_to(&mut drone);
+        drone.update(&txn).await?;
+    }
+
+    txn.commit().await?;
+
+    for update in updates {
+        hub.publish(HubMessage::Telemetry(update));
+    }
+
+    Ok(
+        ApiResponse::ok(
+            t!("telemetry.ingested"),
+            Some(TelemetryBatchResponse { accepted, drones: drone_ids.len() })
+        )
+    )
+}
+
+#[derive(Debug, Deserialize)]
+struct TelemetryQuery {
+    #[serde(flatten)]
+    pagination: PaginationParams,
+    #[serde(flatten)]
+    cursor: CursorParams,
+    #[serde(flatten)]
+    list: ListQuery,
+}
+
+impl Queryable for Telemetry {
+    fn fields() -> Vec<QueryField<telemetry::Column>> {
+        vec![
+            QueryField::new("drone_id", telemetry::Column::DroneId, FieldKind::Text),
+            QueryField::new("mission_id", telemetry::Column::MissionId, FieldKind::Text),
+            QueryField::new("altitude", telemetry::Column::Altitude, FieldKind::Float),
+            QueryField::new("speed", telemetry::Column::Speed, FieldKind::Float),
+            QueryField::new("battery", telemetry::Column::Battery, FieldKind::Integer),
+            QueryField::new("recorded_at", telemetry::Column::RecordedAt, FieldKind::DateTime)
+        ]
+    }
+
+    fn default_sort() -> Vec<(telemetry::Column, Order)> {
+        vec![
+            (telemetry::Column::RecordedAt, Order::Desc),
+            (telemetry::Column::TelemetryId, Order::Desc)
+        ]
+    }
+}
+
+type TelemetryListParams = ListParams<Telemetry>;
+
+// 遥测按采集时间遍历，与页码分页的排序一致
+impl Keyset for Telemetry {
+    fn time_column() -> Option<telemetry::Column> {
+        Some(telemetry::Column::RecordedAt)
+    }
+
+    fn id_column() -> telemetry::Column {
+        telemetry::Column::TelemetryId
+    }
+
+    fn cursor_of(model: &telemetry::Model) -> Cursor {
+        Cursor { time: Some(model.recorded_at), id: CursorId::Int(model.telemetry_id) }
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/telemetry",
+    tag = "telemetry",
+    params(PaginationParams, CursorParams, TelemetryListParams),
+    responses((status = 200, description = "遥测记录，带 cursor 或 limit 时使用游标分页", body = ApiResponse<Listing<telemetry::Model>>))
+)]
+async fn get_all_telemetry(
+    State(AppState { db, .. }): State<AppState>,
+    ValidQuery(TelemetryQuery { pagination, cursor, list }): ValidQuery<TelemetryQuery>
+) -> ApiResult<ApiResponse<Listing<telemetry::Model>>> {
+    if cursor.is_active() {
+        let select = list.apply_for_cursor(Telemetry::find())?;
+        let page = cursor::fetch(&db, select, &cursor).await?;
+        return Ok(ApiResponse::ok(t!("telemetry.list"), Some(Listing::Cursor(page))));
+    }
+
+    let paginator = list.apply(Telemetry::find())?.paginate(&db, pagination.size);
+    let total = paginator.num_items().await?;
+    let records = paginator.fetch_page(pagination.index()).await?;
+
+    let page = Page::from_pagination(pagination, total, records);
+
+    Ok(ApiResponse::ok(t!("telemetry.list"), Some(Listing::Page(page))))
+}
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(
+        ingest_telemetry,
+        get_all_telemetry
+    ),
+    tags((name = "telemetry", description = "遥测数据"))
+)]
+pub struct TelemetryApi;
+
+pub fn create_telemetry_router() -> Router<AppState> {
+    Router::new()
+        .route("/", get(get_all_telemetry))
+        .route("/batch", post(ingest_telemetry))
+}
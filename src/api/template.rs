@@ -0,0 +1,727 @@
+use std::str::FromStr;
+
+use axum::extract::State;
+use axum::Router;
+use axum::routing::{ get, post };
+use cron::Schedule;
+use sea_orm::entity::prelude::*;
+use sea_orm::{ ActiveValue, IntoActiveModel, Order, QueryFilter, QueryOrder, TransactionTrait };
+use serde::{ Deserialize, Serialize };
+use utoipa::{ IntoParams, OpenApi, ToSchema };
+use validator::{ Validate, ValidationError };
+
+use crate::api::dispatch::{ reserved_drones, MIN_DISPATCH_BATTERY };
+use crate::api::geofence;
+use crate::api::logs::LogManager;
+use crate::api::mission::ensure_drone_available;
+use crate::api::waypoint::{ WaypointRequest, MAX_WAYPOINTS };
+use crate::app::AppState;
+use crate::common::auth;
+use crate::common::jwt::Claims;
+use crate::common::page::{ Page, PaginationParams };
+use crate::common::query::{ FieldKind, ListParams, ListQuery, QueryField, Queryable };
+use crate::common::response::{ ApiResponse, MessageResponse };
+use crate::common::result::{ ApiError, ApiResult };
+use crate::common::time;
+use crate::common::validation::{ self, ValidJson, ValidPath, ValidQuery };
+use crate::entity::mission_templates::{
+    ActiveModel as MissionTemplatesActiveModel,
+    TemplateWaypoint,
+    TemplateWaypoints,
+};
+use crate::entity::mission_waypoints::ActiveModel as WaypointsActiveModel;
+use crate::entity::missions::ActiveModel as MissionsActiveModel;
+use crate::entity::prelude::{ Drones, MissionTemplates, Missions };
+use crate::entity::sea_orm_active_enums::Status;
+use crate::entity::{ drones, mission_templates, mission_waypoints, missions };
+use crate::hub::Hub;
+use crate::t;
+
+// 即将执行列表默认和最大返回条数
+const DEFAULT_UPCOMING_LIMIT: usize = 20;
+const MAX_UPCOMING_LIMIT: usize = 100;
+
+const WEEKDAYS: [&str; 7] = ["SUN", "MON", "TUE", "WED", "THU", "FRI", "SAT"];
+
+// cron 库的周字段为 1-7（1 为周日），把标准写法的数字 0-7（0 和 7 为周日）展开成星期名称，
+// 带步长的单个起点与标准 cron 一致，一直取到 7
+fn weekday_field(field: &str) -> Option<String> {
+    let items = field
+        .split(',')
+        .map(|item| {
+            let (range, step) = match item.split_once('/') {
+                Some((range, step)) => (range, Some(step.parse::<usize>().ok().filter(|step| *step > 0)?)),
+                None => (item, None),
+            };
+            if !range.starts_with(|c: char| c.is_ascii_digit()) {
+                return Some(item.to_string());
+            }
+
+            let (start, end): (usize, usize) = match range.split_once('-') {
+                Some((start, end)) => (start.parse().ok()?, end.parse().ok()?),
+                None => {
+                    let start = range.parse().ok()?;
+                    (start, if step.is_some() { 7 } else { start })
+                }
+            };
+            if start > end || end > 7 {
+                return None;
+            }
+
+            let days: Vec<&str> = (start..=end)
+                .step_by(step.unwrap_or(1))
+                .map(|day| WEEKDAYS[day % 7])
+                .collect();
+            Some(days.join(","))
+        })
+        .collect::<Option<Vec<_>>>()?;
+    Some(items.join(","))
+}
+
+// 标准 5 段 cron 表达式（分 时 日 月 周），按 UTC 计算
+fn parse_cron(expression: &str) -> Option<Schedule> {
+    let fields: Vec<&str> = expression.split_whitespace().collect();
+    let [minute, hour, day, month, weekday] = fields[..] else {
+        return None;
+    };
+    let weekday = weekday_field(weekday)?;
+    Schedule::from_str(&format!("0 {} {} {} {} {}", minute, hour, day, month, weekday)).ok()
+}
+
+fn validate_cron(expression: &str) -> Result<(), ValidationError> {
+    match parse_cron(expression) {
+        Some(_) => Ok(()),
+        None => Err(ValidationError::new("cron")),
+    }
+}
+
+// after 之后的执行时间
+fn runs_after(schedule: &Schedule, after: DateTime) -> impl Iterator<Item = DateTime> + '_ {
+    schedule.after(&after.and_utc()).map(|run_at| run_at.naive_utc())
+}
+
+fn next_run(expression: &str, after: DateTime) -> Option<DateTime> {
+    parse_cron(expression).and_then(|schedule| runs_after(&schedule, after).next())
+}
+
+#[derive(Debug, Deserialize)]
+struct TemplateQuery {
+    #[serde(flatten)]
+    pagination: PaginationParams,
+    #[serde(flatten)]
+    list: ListQuery,
+}
+
+impl Queryable for MissionTemplates {
+    fn fields() -> Vec<QueryField<mission_templates::Column>> {
+        vec![
+            QueryField::new("name", mission_templates::Column::Name, FieldKind::Text),
+            QueryField::new("user_id", mission_templates::Column::UserId, FieldKind::Text),
+            QueryField::new("drone_id", mission_templates::Column::DroneId, FieldKind::Text),
+            QueryField::new("drone_model", mission_templates::Column::DroneModel, FieldKind::Text),
+            QueryField::new("last_run_at", mission_templates::Column::LastRunAt, FieldKind::DateTime),
+            QueryField::new("next_run_at", mission_templates::Column::NextRunAt, FieldKind::DateTime),
+            QueryField::new("created_at", mission_templates::Column::CreatedAt, FieldKind::DateTime)
+        ]
+    }
+
+    fn search_columns() -> Vec<mission_templates::Column> {
+        vec![mission_templates::Column::Name]
+    }
+
+    fn default_sort() -> Vec<(mission_templates::Column, Order)> {
+        vec![
+            (mission_templates::Column::CreatedAt, Order::Desc),
+            (mission_templates::Column::TemplateId, Order::Desc)
+        ]
+    }
+}
+
+type TemplateListParams = ListParams<MissionTemplates>;
+
+// 指定无人机或无人机型号二选一，按型号时每次执行选择电量最高的空闲无人机
+#[derive(Debug, Deserialize, ToSchema, Validate)]
+#[serde(rename_all = "camelCase")]
+#[validate(schema(function = "validate_template"))]
+struct TemplateRequest {
+    #[validate(length(min = 1, max = 100))]
+    name: String,
+    /// 任务发起人，默认为当前用户
+    #[validate(length(min = 1, max = 32))]
+    user_id: Option<String>,
+    #[validate(length(min = 1, max = 32))]
+    drone_id: Option<String>,
+    #[validate(length(min = 1, max = 50))]
+    drone_model: Option<String>,
+    #[validate(custom(function = "validation::latitude"))]
+    target_lat: Decimal,
+    #[validate(custom(function = "validation::longitude"))]
+    target_lng: Decimal,
+    /// 每次生成任务时复制的航线，按数组顺序编号
+    #[serde(default)]
+    #[validate(length(max = MAX_WAYPOINTS), nested)]
+    waypoints: Vec<WaypointRequest>,
+    /// 5 段 cron 表达式（分 时 日 月 周，UTC），例如 "0 6 * * *" 表示每天 06:00；
+    /// 周字段为 0-7（0 和 7 均为周日）或 SUN-SAT，例如 "0 6 * * 1-5" 表示周一至周五 06:00
+    #[validate(custom(function = "validate_cron"))]
+    cron: String,
+    #[serde(default)]
+    paused: bool,
+}
+
+fn validate_template(data: &TemplateRequest) -> Result<(), ValidationError> {
+    if data.drone_id.is_some() == data.drone_model.is_some() {
+        return Err(ValidationError::new("template_drone"));
+    }
+    Ok(())
+}
+
+impl TemplateRequest {
+    // 按生成任务时的编号构造航线，保存前做与每次生成任务相同的禁飞区检查
+    fn route(&self) -> Vec<mission_waypoints::Model> {
+        let now = time::now();
+        (1..)
+            .zip(&self.waypoints)
+            .map(|(seq, waypoint)| mission_waypoints::Model {
+                waypoint_id: 0,
+                mission_id: String::new(),
+                seq,
+                lat: waypoint.lat,
+                lng: waypoint.lng,
+                altitude: waypoint.altitude,
+                loiter_seconds: waypoint.loiter_seconds,
+                action: waypoint.action.clone(),
+                created_at: now,
+            })
+            .collect()
+    }
+
+    fn apply_to(self, template: &mut MissionTemplatesActiveModel, user_id: String) {
+        let waypoints = self.waypoints
+            .into_iter()
+            .map(|waypoint| TemplateWaypoint {
+                lat: waypoint.lat,
+                lng: waypoint.lng,
+                altitude: waypoint.altitude,
+                loiter_seconds: waypoint.loiter_seconds,
+                action: waypoint.action,
+            })
+            .collect::<Vec<_>>();
+        let next_run_at = if self.paused { None } else { next_run(&self.cron, time::now()) };
+
+        template.name = ActiveValue::set(self.name);
+        template.user_id = ActiveValue::set(user_id);
+        template.drone_id = ActiveValue::set(self.drone_id);
+        template.drone_model = ActiveValue::set(self.drone_model);
+        template.target_lat = ActiveValue::set(self.target_lat);
+        template.target_lng = ActiveValue::set(self.target_lng);
+        template.waypoints = ActiveValue::set(
+            (!waypoints.is_empty()).then_some(TemplateWaypoints(waypoints))
+        );
+        template.cron = ActiveValue::set(self.cron);
+        template.paused = ActiveValue::set(self.paused);
+        template.next_run_at = ActiveValue::set(next_run_at);
+    }
+}
+
+async fn find_template(
+    db: &DatabaseConnection,
+    hub: &Hub,
+    claims: &Claims,
+    id: &str,
+    action: &str
+) -> ApiResult<mission_templates::Model> {
+    let Some(template) = MissionTemplates::find_by_id(id).one(db).await? else {
+        return Err(ApiError::NotFound(t!("template.not_found", id = id)));
+    };
+    if !claims.is_self_or_admin(&template.user_id) {
+        return Err(auth::deny(db, hub, claims, &format!("{}任务模板<{}>", action, id)).await);
+    }
+    Ok(template)
+}
+
+// 指定的无人机需可用；按型号时选择已激活、空闲、电量充足且未被占用的无人机中电量最高的一架
+async fn pick_drone<C: ConnectionTrait>(
+    conn: &C,
+    template: &mission_templates::Model
+) -> ApiResult<drones::Model> {
+    if let Some(drone_id) = &template.drone_id {
+        return ensure_drone_available(conn, drone_id, None).await;
+    }
+
+    // 指定的无人机被删除后模板既没有无人机也没有型号
+    let Some(model) = &template.drone_model else {
+        return Err(ApiError::Conflict(t!("template.drone_removed")));
+    };
+    let busy = reserved_drones(conn).await?;
+
+    Drones::find()
+        .filter(drones::Column::Model.eq(model))
+        .filter(drones::Column::Activate.eq(1))
+        .filter(drones::Column::Status.eq(Status::Idle))
+        .filter(drones::Column::Battery.gte(MIN_DISPATCH_BATTERY))
+        .filter(drones::Column::DroneId.is_not_in(busy))
+        .order_by_desc(drones::Column::Battery)
+        .one(conn).await?
+        .ok_or_else(|| ApiError::Conflict(t!("template.no_drone", model = model)))
+}
+
+// 按模板生成一个待执行任务及其航线，航线进入禁飞区时整体回滚
+async fn materialize(
+    db: &DatabaseConnection,
+    template: &mission_templates::Model
+) -> ApiResult<missions::Model> {
+    let txn = db.begin().await?;
+
+    let drone = pick_drone(&txn, template).await?;
+    let mission = MissionsActiveModel {
+        mission_id: ActiveValue::set(xid::new().to_string()),
+        user_id: ActiveValue::set(template.user_id.clone()),
+        drone_id: ActiveValue::set(drone.drone_id),
+        target_lat: ActiveValue::set(Some(template.target_lat)),
+        target_lng: ActiveValue::set(Some(template.target_lng)),
+        status: ActiveValue::set(Status::Idle),
+        template_id: ActiveValue::set(Some(template.template_id.clone())),
+        ..Default::default()
+    };
+    let mission = mission.insert(&txn).await?;
+
+    let mut waypoints = Vec::new();
+    let route = template.waypoints.as_ref().map_or(&[][..], |waypoints| &waypoints.0);
+    for (seq, waypoint) in (1..).zip(route) {
+        let waypoint = WaypointsActiveModel {
+            mission_id: ActiveValue::set(mission.mission_id.clone()),
+            seq: ActiveValue::set(seq),
+            lat: ActiveValue::set(waypoint.lat),
+            lng: ActiveValue::set(waypoint.lng),
+            altitude: ActiveValue::set(waypoint.altitude),
+            loiter_seconds: ActiveValue::set(waypoint.loiter_seconds),
+            action: ActiveValue::set(waypoint.action.clone()),
+            ..Default::default()
+        };
+        waypoints.push(waypoint.insert(&txn).await?);
+    }
+
+    let target = Some((template.target_lat, template.target_lng));
+    geofence::check_route(&txn, target, &waypoints).await?.ensure_clear()?;
+
+    txn.commit().await?;
+    Ok(mission)
+}
+
+// 执行一次到期模板并推进下次执行时间，返回是否生成了任务
+async fn run_template(
+    db: &DatabaseConnection,
+    hub: &Hub,
+    template: mission_templates::Model,
+    now: DateTime
+) -> ApiResult<bool> {
+    let result = materialize(db, &template).await;
+
+    let template_id = template.template_id.clone();
+    // 无人机已被删除的模板暂停，修改模板后再恢复
+    let orphaned = template.drone_id.is_none() && template.drone_model.is_none();
+    let next_run_at = if orphaned { None } else { next_run(&template.cron, now) };
+    let mut template = template.into_active_model();
+    template.last_run_at = ActiveValue::set(Some(now));
+    template.next_run_at = ActiveValue::set(next_run_at);
+    if orphaned {
+        template.paused = ActiveValue::set(true);
+    }
+    template.update(db).await?;
+
+    match result {
+        Ok(mission) => {
+            LogManager::create_log(
+                db,
+                hub,
+                LogManager::info(),
+                format!("任务模板<{}>生成任务<{}>，无人机<{}>", template_id, mission.mission_id, mission.drone_id)
+            ).await?;
+            Ok(true)
+        }
+        Err(e) => {
+            LogManager::create_log(
+                db,
+                hub,
+                LogManager::warning(),
+                format!("任务模板<{}>本次执行跳过: {}", template_id, e.message())
+            ).await?;
+            Ok(false)
+        }
+    }
+}
+
+// 为到期的模板生成任务，无论成功与否都推进到下一次执行时间，停机期间错过的执行不补跑
+pub async fn run_due_templates(db: &DatabaseConnection, hub: &Hub) -> ApiResult<usize> {
+    let now = time::now();
+    let templates = MissionTemplates::find()
+        .filter(mission_templates::Column::Paused.eq(false))
+        .filter(mission_templates::Column::NextRunAt.lte(now))
+        .order_by_asc(mission_templates::Column::NextRunAt)
+        .all(db).await?;
+
+    let mut created = 0;
+    for template in templates {
+        let template_id = template.template_id.clone();
+        // 单个模板出错只记录，不影响其余到期模板
+        match run_template(db, hub, template, now).await {
+            Ok(true) => created += 1,
+            Ok(false) => {}
+            Err(e) => tracing::error!("执行任务模板<{}>失败: {}", template_id, e),
+        }
+    }
+
+    Ok(created)
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/mission-templates",
+    tag = "mission-templates",
+    params(PaginationParams, TemplateListParams),
+    responses((status = 200, description = "任务模板列表", body = ApiResponse<Page<mission_templates::Model>>))
+)]
+async fn get_all_templates(
+    claims: Claims,
+    State(AppState { db, .. }): State<AppState>,
+    ValidQuery(TemplateQuery { pagination, list }): ValidQuery<TemplateQuery>
+) -> ApiResult<ApiResponse<Page<mission_templates::Model>>> {
+    let mut select = list.apply(MissionTemplates::find())?;
+
+    // 普通用户只能查看自己的模板
+    if !claims.is_admin() {
+        select = select.filter(mission_templates::Column::UserId.eq(&claims.user_id));
+    }
+
+    let paginator = select.paginate(&db, pagination.size);
+
+    let total = paginator.num_items().await?;
+    let templates = paginator.fetch_page(pagination.index()).await?;
+
+    let page = Page::from_pagination(pagination, total, templates);
+    Ok(ApiResponse::ok(t!("template.list"), Some(page)))
+}
+
+#[derive(Debug, Deserialize, IntoParams, Validate)]
+#[serde(rename_all = "camelCase")]
+#[into_params(parameter_in = Query)]
+struct UpcomingParams {
+    /// 只查看指定模板
+    template_id: Option<String>,
+    /// 返回条数，默认 20
+    #[param(minimum = 1, maximum = 100)]
+    #[validate(range(min = 1, max = 100))]
+    limit: Option<usize>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+struct UpcomingRun {
+    template_id: String,
+    name: String,
+    run_at: DateTime,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/mission-templates/upcoming",
+    tag = "mission-templates",
+    params(UpcomingParams),
+    responses((status = 200, description = "未暂停模板即将执行的时间，按时间先后排序", body = ApiResponse<Vec<UpcomingRun>>))
+)]
+async fn get_upcoming_runs(
+    claims: Claims,
+    State(AppState { db, .. }): State<AppState>,
+    ValidQuery(params): ValidQuery<UpcomingParams>
+) -> ApiResult<ApiResponse<Vec<UpcomingRun>>> {
+    params.validate().map_err(ApiError::Validation)?;
+    let limit = params.limit.unwrap_or(DEFAULT_UPCOMING_LIMIT).min(MAX_UPCOMING_LIMIT);
+
+    let mut select = MissionTemplates::find().filter(mission_templates::Column::Paused.eq(false));
+    if let Some(template_id) = &params.template_id {
+        select = select.filter(mission_templates::Column::TemplateId.eq(template_id));
+    }
+    if !claims.is_admin() {
+        select = select.filter(mission_templates::Column::UserId.eq(&claims.user_id));
+    }
+    let templates = select.all(&db).await?;
+
+    // 每个模板最多取 limit 次，合并后再截取最早的 limit 次
+    let now = time::now();
+    let mut runs: Vec<UpcomingRun> = Vec::new();
+    for template in &templates {
+        let Some(schedule) = parse_cron(&template.cron) else {
+            continue;
+        };
+        // 到期但尚未被调度器处理的一次也算在内
+        let first = template.next_run_at.filter(|next_run_at| *next_run_at <= now);
+        let after = first.unwrap_or(now);
+        runs.extend(
+            first
+                .into_iter()
+                .chain(runs_after(&schedule, after))
+                .take(limit)
+                .map(|run_at| UpcomingRun {
+                    template_id: template.template_id.clone(),
+                    name: template.name.clone(),
+                    run_at,
+                })
+        );
+    }
+    runs.sort_by_key(|run| run.run_at);
+    runs.truncate(limit);
+
+    Ok(ApiResponse::ok(t!("template.upcoming"), Some(runs)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/mission-templates/{id}",
+    tag = "mission-templates",
+    params(("id" = String, Path, description = "任务模板 ID")),
+    responses((status = 200, description = "任务模板详情", body = ApiResponse<mission_templates::Model>))
+)]
+async fn get_template(
+    claims: Claims,
+    State(AppState { db, hub, .. }): State<AppState>,
+    ValidPath(id): ValidPath<String>
+) -> ApiResult<ApiResponse<mission_templates::Model>> {
+    let template = find_template(&db, &hub, &claims, &id, "查看").await?;
+    Ok(ApiResponse::ok(t!("template.detail"), Some(template)))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/mission-templates",
+    tag = "mission-templates",
+    request_body = TemplateRequest,
+    responses(
+        (status = 200, description = "创建成功", body = ApiResponse<mission_templates::Model>),
+        (status = 409, description = "目标点或航线进入禁飞区")
+    )
+)]
+async fn add_template(
+    claims: Claims,
+    State(AppState { db, hub, .. }): State<AppState>,
+    ValidJson(data): ValidJson<TemplateRequest>
+) -> ApiResult<ApiResponse<mission_templates::Model>> {
+    let user_id = data.user_id.clone().unwrap_or_else(|| claims.user_id.clone());
+    if !claims.is_self_or_admin(&user_id) {
+        return Err(auth::deny(&db, &hub, &claims, "为其他用户创建任务模板").await);
+    }
+    let target = Some((data.target_lat, data.target_lng));
+    geofence::check_route(&db, target, &data.route()).await?.ensure_clear()?;
+
+    let mut template = MissionTemplatesActiveModel {
+        template_id: ActiveValue::set(xid::new().to_string()),
+        ..Default::default()
+    };
+    data.apply_to(&mut template, user_id);
+
+    let template = template.insert(&db).await?;
+    Ok(ApiResponse::ok(t!("template.created"), Some(template)))
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/mission-templates/{id}",
+    tag = "mission-templates",
+    params(("id" = String, Path, description = "任务模板 ID")),
+    request_body = TemplateRequest,
+    responses(
+        (status = 200, description = "更新成功，模板整体替换并重新计算下次执行时间", body = ApiResponse<mission_templates::Model>),
+        (status = 409, description = "目标点或航线进入禁飞区")
+    )
+)]
+async fn update_template(
+    claims: Claims,
+    State(AppState { db, hub, .. }): State<AppState>,
+    ValidPath(id): ValidPath<String>,
+    ValidJson(data): ValidJson<TemplateRequest>
+) -> ApiResult<ApiResponse<mission_templates::Model>> {
+    let template = find_template(&db, &hub, &claims, &id, "修改").await?;
+
+    let user_id = data.user_id.clone().unwrap_or_else(|| template.user_id.clone());
+    if !claims.is_self_or_admin(&user_id) {
+        return Err(auth::deny(&db, &hub, &claims, &format!("将任务模板<{}>转给其他用户", id)).await);
+    }
+    let target = Some((data.target_lat, data.target_lng));
+    geofence::check_route(&db, target, &data.route()).await?.ensure_clear()?;
+
+    let mut template = template.into_active_model();
+    data.apply_to(&mut template, user_id);
+
+    let template = template.update(&db).await?;
+    Ok(ApiResponse::ok(t!("template.updated"), Some(template)))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/mission-templates/{id}",
+    tag = "mission-templates",
+    params(("id" = String, Path, description = "任务模板 ID")),
+    responses((status = 200, description = "删除成功，已生成的任务保留", body = MessageResponse))
+)]
+async fn delete_template(
+    claims: Claims,
+    State(AppState { db, hub, .. }): State<AppState>,
+    ValidPath(id): ValidPath<String>
+) -> ApiResult<ApiResponse<()>> {
+    let template = find_template(&db, &hub, &claims, &id, "删除").await?;
+
+    let txn = db.begin().await?;
+
+    // 已生成的任务保留，只解除与模板的关联
+    Missions::update_many()
+        .col_expr(missions::Column::TemplateId, Expr::value(Option::<String>::None))
+        .filter(missions::Column::TemplateId.eq(&id))
+        .exec(&txn).await?;
+
+    template.delete(&txn).await?;
+
+    txn.commit().await?;
+    Ok(ApiResponse::ok(t!("template.deleted"), None))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/mission-templates/{id}/pause",
+    tag = "mission-templates",
+    params(("id" = String, Path, description = "任务模板 ID")),
+    responses((status = 200, description = "已暂停，不再生成任务", body = ApiResponse<mission_templates::Model>))
+)]
+async fn pause_template(
+    claims: Claims,
+    State(AppState { db, hub, .. }): State<AppState>,
+    ValidPath(id): ValidPath<String>
+) -> ApiResult<ApiResponse<mission_templates::Model>> {
+    let template = find_template(&db, &hub, &claims, &id, "暂停").await?;
+
+    let mut template = template.into_active_model();
+    template.paused = ActiveValue::set(true);
+    template.next_run_at = ActiveValue::set(None);
+
+    let template = template.update(&db).await?;
+    Ok(ApiResponse::ok(t!("template.paused"), Some(template)))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/mission-templates/{id}/resume",
+    tag = "mission-templates",
+    params(("id" = String, Path, description = "任务模板 ID")),
+    responses(
+        (status = 200, description = "已恢复，从当前时间起计算下次执行时间", body = ApiResponse<mission_templates::Model>),
+        (status = 409, description = "模板指定的无人机已被删除")
+    )
+)]
+async fn resume_template(
+    claims: Claims,
+    State(AppState { db, hub, .. }): State<AppState>,
+    ValidPath(id): ValidPath<String>
+) -> ApiResult<ApiResponse<mission_templates::Model>> {
+    let template = find_template(&db, &hub, &claims, &id, "恢复").await?;
+    if template.drone_id.is_none() && template.drone_model.is_none() {
+        return Err(ApiError::Conflict(t!("template.drone_removed")));
+    }
+
+    let next_run_at = next_run(&template.cron, time::now());
+    let mut template = template.into_active_model();
+    template.paused = ActiveValue::set(false);
+    template.next_run_at = ActiveValue::set(next_run_at);
+
+    let template = template.update(&db).await?;
+    Ok(ApiResponse::ok(t!("template.resumed"), Some(template)))
+}
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(
+        get_all_templates,
+        get_upcoming_runs,
+        get_template,
+        add_template,
+        update_template,
+        delete_template,
+        pause_template,
+        resume_template
+    ),
+    tags((name = "mission-templates", description = "任务模板与定时任务"))
+)]
+pub struct TemplateApi;
+
+pub fn create_template_router() -> Router<AppState> {
+    Router::new()
+        .route("/", get(get_all_templates).post(add_template))
+        .route("/upcoming", get(get_upcoming_runs))
+        .route("/{id}", get(get_template).put(update_template).delete(delete_template))
+        .route("/{id}/pause", post(pause_template))
+        .route("/{id}/resume", post(resume_template))
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{ Datelike, NaiveDate, Weekday };
+
+    use super::*;
+
+    #[test]
+    fn weekday_field_expands_numbers() {
+        assert_eq!(weekday_field("0").as_deref(), Some("SUN"));
+        assert_eq!(weekday_field("7").as_deref(), Some("SUN"));
+        assert_eq!(weekday_field("1-5").as_deref(), Some("MON,TUE,WED,THU,FRI"));
+        assert_eq!(weekday_field("1,3").as_deref(), Some("MON,WED"));
+    }
+
+    #[test]
+    fn weekday_field_steps_up_to_seven() {
+        assert_eq!(weekday_field("5/2").as_deref(), Some("FRI,SUN"));
+        assert_eq!(weekday_field("1/3").as_deref(), Some("MON,THU,SUN"));
+        assert_eq!(weekday_field("1-5/2").as_deref(), Some("MON,WED,FRI"));
+    }
+
+    #[test]
+    fn weekday_field_keeps_names() {
+        assert_eq!(weekday_field("MON-FRI").as_deref(), Some("MON-FRI"));
+        assert_eq!(weekday_field("*").as_deref(), Some("*"));
+    }
+
+    #[test]
+    fn weekday_field_rejects_invalid() {
+        assert_eq!(weekday_field("8"), None);
+        assert_eq!(weekday_field("5-3"), None);
+        assert_eq!(weekday_field("1/0"), None);
+        assert_eq!(weekday_field("1/x"), None);
+    }
+
+    #[test]
+    fn parse_cron_requires_five_fields() {
+        assert!(parse_cron("0 6 * * *").is_some());
+        assert!(parse_cron("0 6 * *").is_none());
+        assert!(parse_cron("0 0 6 * * *").is_none());
+        assert!(parse_cron("61 6 * * *").is_none());
+    }
+
+    #[test]
+    fn parse_cron_treats_zero_and_seven_as_sunday() {
+        // 2026-10-17 是周六
+        let after = NaiveDate::from_ymd_opt(2026, 10, 17).unwrap().and_hms_opt(12, 0, 0).unwrap();
+        for expression in ["0 6 * * 0", "0 6 * * 7"] {
+            let run_at = next_run(expression, after).unwrap();
+            assert_eq!(run_at.weekday(), Weekday::Sun);
+            let sunday = NaiveDate::from_ymd_opt(2026, 10, 18).unwrap();
+            assert_eq!(run_at, sunday.and_hms_opt(6, 0, 0).unwrap());
+        }
+    }
+
+    #[test]
+    fn parse_cron_steps_include_sunday() {
+        // 周五 06:00 之后的下一次为周日
+        let after = NaiveDate::from_ymd_opt(2026, 10, 16).unwrap().and_hms_opt(12, 0, 0).unwrap();
+        let run_at = next_run("0 6 * * 5/2", after).unwrap();
+        assert_eq!(run_at.weekday(), Weekday::Sun);
+        // 从 0 开始的步长会同时展开 0 和 7，两者都是周日
+        assert!(parse_cron("0 6 * * 0/1").is_some());
+    }
+}
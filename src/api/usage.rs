@@ -0,0 +1,485 @@
+use std::collections::HashMap;
+
+use axum::extract::State;
+use axum::Router;
+use axum::routing::get;
+use chrono::NaiveDateTime;
+use sea_orm::entity::prelude::*;
+use sea_orm::sea_query::{ Query, SimpleExpr, SubQueryStatement };
+use sea_orm::{ Condition, QueryOrder, QuerySelect };
+use serde::Serialize;
+use utoipa::{ OpenApi, ToSchema };
+
+use crate::api::mission::ACTIVE_STATUSES;
+use crate::app::AppState;
+use crate::common::config::MaintenanceConfig;
+use crate::common::response::ApiResponse;
+use crate::common::result::{ ApiError, ApiResult };
+use crate::common::validation::ValidPath;
+use crate::common::time;
+use crate::entity::prelude::{ Drones, Events, MaintenanceRecords, Missions };
+use crate::entity::sea_orm_active_enums::EventType;
+use crate::entity::{ drones, events, maintenance_records, missions };
+use crate::t;
+
+const SECONDS_PER_HOUR: f64 = 3600.0;
+const SECONDS_PER_DAY: f64 = 86400.0;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, ToSchema)]
+enum ServiceDueReason {
+    /// 飞行小时数超出保养间隔
+    Hours,
+    /// 起降次数超出保养间隔
+    Cycles,
+    /// 距上次保养天数超出保养间隔
+    Days,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+struct UsageSinceMaintenance {
+    flight_hours: f64,
+    flight_cycles: u64,
+    /// 距上次保养结束的天数，从未保养过时从首次执行任务算起，从未执行过任务时为空
+    days: Option<f64>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+struct DroneUsage {
+    drone_id: String,
+    name: String,
+    /// 累计飞行小时数
+    flight_hours: f64,
+    /// 累计起降次数
+    flight_cycles: u64,
+    /// 最近一次保养结束时间
+    last_maintenance_at: Option<DateTime>,
+    /// 上次保养后的使用量，从未保养过时与累计值相同
+    since_maintenance: UsageSinceMaintenance,
+    service_due: bool,
+    due_reasons: Vec<ServiceDueReason>,
+}
+
+// 一次飞行：起飞到降落（仍在飞行时结束时间为当前时间，不计起降次数）
+struct Flight {
+    start: NaiveDateTime,
+    end: NaiveDateTime,
+    landed: bool,
+}
+
+// 优先按起飞/降落事件配对；任务没有成对事件时用任务的开始和结束时间代替
+fn mission_flights(mission: &missions::Model, events: &[events::Model], now: NaiveDateTime) -> Vec<Flight> {
+    let active = ACTIVE_STATUSES.contains(&mission.status);
+    let mut flights = Vec::new();
+    let mut takeoff = None;
+
+    for event in events {
+        match event.event_type {
+            EventType::Takeoff => {
+                takeoff = Some(event.created_at);
+            }
+            EventType::Landing => {
+                if let Some(start) = takeoff.take() {
+                    flights.push(Flight { start, end: event.created_at, landed: true });
+                }
+            }
+            _ => {}
+        }
+    }
+    if let Some(start) = takeoff && active {
+        flights.push(Flight { start, end: now, landed: false });
+    }
+
+    if flights.is_empty() && let Some(start) = mission.started_at {
+        match mission.completed_at {
+            Some(end) => flights.push(Flight { start, end, landed: true }),
+            None if active => flights.push(Flight { start, end: now, landed: false }),
+            None => {}
+        }
+    }
+
+    flights
+}
+
+// 汇总 since 之后的飞行秒数和起降次数，跨越 since 的飞行只计后半段
+fn total(flights: &[Flight], since: Option<NaiveDateTime>) -> (i64, u64) {
+    let mut seconds = 0;
+    let mut cycles = 0;
+
+    for flight in flights {
+        let start = since.map_or(flight.start, |since| flight.start.max(since));
+        if flight.end > start {
+            seconds += (flight.end - start).num_seconds();
+        }
+        if flight.landed && since.is_none_or(|since| flight.end > since) {
+            cycles += 1;
+        }
+    }
+
+    (seconds, cycles)
+}
+
+fn usage_of(
+    drone: drones::Model,
+    flights: &[Flight],
+    last_maintenance_at: Option<NaiveDateTime>,
+    first_started_at: Option<NaiveDateTime>,
+    config: &MaintenanceConfig,
+    now: NaiveDateTime
+) -> DroneUsage {
+    let (seconds, cycles) = total(flights, None);
+    let (since_seconds, since_cycles) = total(flights, last_maintenance_at);
+
+    let since = UsageSinceMaintenance {
+        flight_hours: (since_seconds as f64) / SECONDS_PER_HOUR,
+        flight_cycles: since_cycles,
+        days: last_maintenance_at
+            .or(first_started_at)
+            .map(|at| ((now - at).num_seconds() as f64) / SECONDS_PER_DAY),
+    };
+
+    let mut due_reasons = Vec::new();
+    if config.service_hours > 0.0 && since.flight_hours >= config.service_hours {
+        due_reasons.push(ServiceDueReason::Hours);
+    }
+    if config.service_cycles > 0 && since.flight_cycles >= config.service_cycles {
+        due_reasons.push(ServiceDueReason::Cycles);
+    }
+    if config.service_days > 0 && since.days.is_some_and(|days| days >= (config.service_days as f64)) {
+        due_reasons.push(ServiceDueReason::Days);
+    }
+
+    DroneUsage {
+        drone_id: drone.drone_id,
+        name: drone.name,
+        flight_hours: (seconds as f64) / SECONDS_PER_HOUR,
+        flight_cycles: cycles,
+        last_maintenance_at,
+        since_maintenance: since,
+        service_due: !due_reasons.is_empty(),
+        due_reasons,
+    }
+}
+
+// 上次保养结束后仍在进行或才结束的任务，从未保养过的无人机取全部任务
+fn since_last_maintenance() -> Condition {
+    let last_maintenance = Query::select()
+        .expr(Expr::col((MaintenanceRecords, maintenance_records::Column::EndedAt)).max())
+        .from(MaintenanceRecords)
+        .and_where(
+            Expr::col((MaintenanceRecords, maintenance_records::Column::DroneId)).equals((
+                Missions,
+                missions::Column::DroneId,
+            ))
+        )
+        .to_owned();
+    let last_maintenance = || {
+        SimpleExpr::SubQuery(None, Box::new(SubQueryStatement::SelectStatement(last_maintenance.clone())))
+    };
+
+    Condition::any()
+        .add(missions::Column::CompletedAt.is_null())
+        .add(Expr::col((Missions, missions::Column::CompletedAt)).gt(last_maintenance()))
+        .add(Expr::expr(last_maintenance()).is_null())
+}
+
+// 按无人机汇总满足条件的已开始任务的飞行记录
+async fn load_flights<C: ConnectionTrait>(
+    conn: &C,
+    condition: Condition,
+    now: NaiveDateTime
+) -> ApiResult<HashMap<String, Vec<Flight>>> {
+    let missions = Missions::find()
+        .filter(missions::Column::StartedAt.is_not_null())
+        .filter(condition)
+        .all(conn).await?;
+
+    let mut events: HashMap<String, Vec<events::Model>> = HashMap::new();
+    for chunk in missions.chunks(500) {
+        let found = Events::find()
+            .filter(events::Column::MissionId.is_in(chunk.iter().map(|mission| mission.mission_id.as_str())))
+            .filter(events::Column::EventType.is_in([EventType::Takeoff, EventType::Landing]))
+            .order_by_asc(events::Column::CreatedAt)
+            .all(conn).await?;
+        for event in found {
+            events.entry(event.mission_id.clone()).or_default().push(event);
+        }
+    }
+
+    let mut flights: HashMap<String, Vec<Flight>> = HashMap::new();
+    for mission in &missions {
+        let mission_events = events.get(&mission.mission_id).map(Vec::as_slice).unwrap_or_default();
+        flights
+            .entry(mission.drone_id.clone())
+            .or_default()
+            .extend(mission_flights(mission, mission_events, now));
+    }
+
+    Ok(flights)
+}
+
+// 每架无人机最近一次已结束的保养时间
+async fn load_last_maintenance<C: ConnectionTrait>(
+    conn: &C,
+    drone_id: Option<&str>
+) -> ApiResult<HashMap<String, NaiveDateTime>> {
+    let mut query = MaintenanceRecords::find()
+        .select_only()
+        .column(maintenance_records::Column::DroneId)
+        .column_as(maintenance_records::Column::EndedAt.max(), "ended_at")
+        .filter(maintenance_records::Column::EndedAt.is_not_null())
+        .group_by(maintenance_records::Column::DroneId);
+    if let Some(drone_id) = drone_id {
+        query = query.filter(maintenance_records::Column::DroneId.eq(drone_id));
+    }
+
+    let rows: Vec<(String, NaiveDateTime)> = query.into_tuple().all(conn).await?;
+    Ok(rows.into_iter().collect())
+}
+
+// 每架无人机首次执行任务的开始时间，用于从未保养过的无人机计算保养天数
+async fn load_first_started<C: ConnectionTrait>(
+    conn: &C,
+    drone_id: Option<&str>
+) -> ApiResult<HashMap<String, NaiveDateTime>> {
+    let mut query = Missions::find()
+        .select_only()
+        .column(missions::Column::DroneId)
+        .column_as(missions::Column::StartedAt.min(), "started_at")
+        .filter(missions::Column::StartedAt.is_not_null())
+        .group_by(missions::Column::DroneId);
+    if let Some(drone_id) = drone_id {
+        query = query.filter(missions::Column::DroneId.eq(drone_id));
+    }
+
+    let rows: Vec<(String, NaiveDateTime)> = query.into_tuple().all(conn).await?;
+    Ok(rows.into_iter().collect())
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/drones/{id}/usage",
+    tag = "drones",
+    params(("id" = String, Path, description = "无人机 ID")),
+    responses((status = 200, description = "累计飞行小时数、起降次数及是否需要保养", body = ApiResponse<DroneUsage>))
+)]
+async fn get_drone_usage(
+    State(AppState { db, config, .. }): State<AppState>,
+    ValidPath(id): ValidPath<String>
+) -> ApiResult<ApiResponse<DroneUsage>> {
+    let Some(drone) = Drones::find_by_id(&id).one(&db).await? else {
+        return Err(ApiError::NotFound(t!("drone.not_found", id = id)));
+    };
+
+    let now = time::now();
+    let flights = load_flights(&db, Condition::all().add(missions::Column::DroneId.eq(&id)), now).await?;
+    let last_maintenance = load_last_maintenance(&db, Some(&id)).await?;
+    let first_started = load_first_started(&db, Some(&id)).await?;
+
+    let usage = usage_of(
+        drone,
+        flights.get(&id).map(Vec::as_slice).unwrap_or_default(),
+        last_maintenance.get(&id).copied(),
+        first_started.get(&id).copied(),
+        &config.maintenance,
+        now
+    );
+    Ok(ApiResponse::ok(t!("drone.usage"), Some(usage)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/drones/service-due",
+    tag = "drones",
+    responses((status = 200, description = "超出保养间隔的无人机，按飞行小时数降序", body = ApiResponse<Vec<DroneUsage>>))
+)]
+async fn get_service_due_drones(
+    State(AppState { db, config, .. }): State<AppState>
+) -> ApiResult<ApiResponse<Vec<DroneUsage>>> {
+    let now = time::now();
+    let drones = Drones::find().all(&db).await?;
+    let last_maintenance = load_last_maintenance(&db, None).await?;
+    let first_started = load_first_started(&db, None).await?;
+
+    // 先只按上次保养后的任务判断是否到期，再为到期的无人机补算累计值
+    let recent = load_flights(&db, since_last_maintenance(), now).await?;
+    let due_drones: Vec<drones::Model> = drones
+        .into_iter()
+        .filter(|drone| {
+            let flights = recent.get(&drone.drone_id).map(Vec::as_slice).unwrap_or_default();
+            let last_maintenance_at = last_maintenance.get(&drone.drone_id).copied();
+            let first_started_at = first_started.get(&drone.drone_id).copied();
+            usage_of(drone.clone(), flights, last_maintenance_at, first_started_at, &config.maintenance, now).service_due
+        })
+        .collect();
+    if due_drones.is_empty() {
+        return Ok(ApiResponse::ok(t!("drone.service_due"), Some(Vec::new())));
+    }
+
+    let due_ids = due_drones.iter().map(|drone| drone.drone_id.clone());
+    let flights = load_flights(&db, Condition::all().add(missions::Column::DroneId.is_in(due_ids)), now).await?;
+    let mut due: Vec<DroneUsage> = due_drones
+        .into_iter()
+        .map(|drone| {
+            let flights = flights.get(&drone.drone_id).map(Vec::as_slice).unwrap_or_default();
+            let last_maintenance_at = last_maintenance.get(&drone.drone_id).copied();
+            let first_started_at = first_started.get(&drone.drone_id).copied();
+            usage_of(drone, flights, last_maintenance_at, first_started_at, &config.maintenance, now)
+        })
+        .collect();
+    due.sort_by(|a, b| b.since_maintenance.flight_hours.total_cmp(&a.since_maintenance.flight_hours));
+
+    Ok(ApiResponse::ok(t!("drone.service_due"), Some(due)))
+}
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(get_drone_usage, get_service_due_drones),
+    components(schemas(DroneUsage, UsageSinceMaintenance, ServiceDueReason))
+)]
+pub struct UsageApi;
+
+pub fn create_usage_router() -> Router<AppState> {
+    Router::new()
+        .route("/{id}/usage", get(get_drone_usage))
+        .route("/service-due", get(get_service_due_drones))
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{ Duration, NaiveDate };
+
+    use super::*;
+    use crate::entity::sea_orm_active_enums::Status;
+
+    fn at(hour: u32, minute: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2025, 7, 1).unwrap().and_hms_opt(hour, minute, 0).unwrap()
+    }
+
+    fn mission(
+        status: Status,
+        started_at: Option<NaiveDateTime>,
+        completed_at: Option<NaiveDateTime>
+    ) -> missions::Model {
+        missions::Model {
+            mission_id: "m1".to_string(),
+            user_id: "u1".to_string(),
+            drone_id: "d1".to_string(),
+            target_lat: None,
+            target_lng: None,
+            status,
+            created_at: at(0, 0),
+            started_at,
+            completed_at,
+            incident_id: None,
+            template_id: None,
+        }
+    }
+
+    fn event(event_type: EventType, created_at: NaiveDateTime) -> events::Model {
+        events::Model {
+            event_id: created_at.to_string(),
+            mission_id: "m1".to_string(),
+            event_type,
+            message: None,
+            created_at,
+        }
+    }
+
+    fn drone() -> drones::Model {
+        drones::Model {
+            drone_id: "d1".to_string(),
+            name: "UAV-1".to_string(),
+            model: "M300".to_string(),
+            status: Status::Idle,
+            battery: 100,
+            activate: 1,
+            last_known_lat: None,
+            last_known_lng: None,
+            last_known_altitude: None,
+            last_seen_at: None,
+        }
+    }
+
+    fn config() -> MaintenanceConfig {
+        MaintenanceConfig { service_hours: 1.0, service_cycles: 0, service_days: 30 }
+    }
+
+    #[test]
+    fn pairs_takeoff_and_landing_events() {
+        let mission = mission(Status::Completed, Some(at(0, 30)), Some(at(4, 0)));
+        let events = [
+            event(EventType::Landing, at(0, 45)),
+            event(EventType::Takeoff, at(1, 0)),
+            event(EventType::BatteryLow, at(1, 30)),
+            event(EventType::Landing, at(2, 0)),
+            event(EventType::Takeoff, at(3, 0)),
+            event(EventType::Landing, at(3, 30)),
+        ];
+
+        let flights = mission_flights(&mission, &events, at(5, 0));
+        assert_eq!(flights.len(), 2);
+        assert!(flights.iter().all(|flight| flight.landed));
+        assert_eq!(total(&flights, None), (5400, 2));
+    }
+
+    #[test]
+    fn falls_back_to_mission_times_without_paired_events() {
+        let completed = mission(Status::Completed, Some(at(1, 0)), Some(at(3, 0)));
+        let dangling = [event(EventType::Takeoff, at(1, 5))];
+
+        for events in [&[][..], &dangling[..]] {
+            let flights = mission_flights(&completed, events, at(5, 0));
+            assert_eq!(total(&flights, None), (7200, 1));
+        }
+
+        let never_started = mission(Status::Idle, None, None);
+        assert!(mission_flights(&never_started, &[], at(5, 0)).is_empty());
+    }
+
+    #[test]
+    fn active_flights_end_now_without_a_cycle() {
+        let working = mission(Status::Working, Some(at(0, 30)), None);
+
+        let flights = mission_flights(&working, &[event(EventType::Takeoff, at(1, 0))], at(2, 0));
+        assert_eq!(flights.len(), 1);
+        assert!(!flights[0].landed);
+        assert_eq!(total(&flights, None), (3600, 0));
+
+        let flights = mission_flights(&working, &[], at(2, 0));
+        assert_eq!(total(&flights, None), (5400, 0));
+    }
+
+    #[test]
+    fn counts_only_the_part_after_maintenance() {
+        let flights = [
+            Flight { start: at(0, 0), end: at(1, 0), landed: true },
+            Flight { start: at(1, 30), end: at(3, 0), landed: true },
+            Flight { start: at(4, 0), end: at(5, 0), landed: true },
+        ];
+
+        assert_eq!(total(&flights, None), (12600, 3));
+        assert_eq!(total(&flights, Some(at(2, 0))), (7200, 2));
+        assert_eq!(total(&flights, Some(at(6, 0))), (0, 0));
+    }
+
+    #[test]
+    fn reports_service_due_reasons() {
+        let now = at(12, 0);
+        let flights = [Flight { start: at(1, 0), end: at(3, 0), landed: true }];
+        let first_started_at = Some(now - Duration::days(40));
+
+        let usage = usage_of(drone(), &flights, None, first_started_at, &config(), now);
+        assert_eq!(usage.flight_cycles, 1);
+        assert!(usage.service_due);
+        assert_eq!(usage.due_reasons, vec![ServiceDueReason::Hours, ServiceDueReason::Days]);
+
+        // 保养之后没有飞行，累计值保留，保养后的使用量清零
+        let usage = usage_of(drone(), &flights, Some(at(4, 0)), first_started_at, &config(), now);
+        assert_eq!(usage.flight_hours, 2.0);
+        assert_eq!(usage.since_maintenance.flight_hours, 0.0);
+        assert_eq!(usage.since_maintenance.flight_cycles, 0);
+        assert!(!usage.service_due);
+    }
+}
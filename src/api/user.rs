@@ -1,36 +1,48 @@
-use axum::extract::{ Json, Path, Query, State };
+use axum::extract::State;
 use axum::Router;
 
 use axum::routing::{ delete, get, post, put };
 use sea_orm::{ QuerySelect };
 
 use serde::{ Deserialize, Serialize };
+use utoipa::{ OpenApi, ToSchema };
+use validator::Validate;
 
 use crate::api::logs::LogManager;
 use crate::app::AppState;
 
+use crate::common::auth::{ self, AdminOnly, Authorized };
+use crate::common::jwt::Claims;
+
 use crate::common::page::{ Page, PaginationParams };
-use crate::common::response::ApiResponse;
+use crate::common::query::{ parse_enum, FieldKind, ListParams, ListQuery, QueryField, Queryable };
+use crate::common::password;
+use crate::common::response::{ ApiResponse, MessageResponse };
 use crate::common::result::{ ApiError, ApiResult };
+use crate::common::validation::{ ValidJson, ValidPath, ValidQuery };
 use crate::entity::prelude::Users;
 use crate::entity::sea_orm_active_enums::Role;
 use crate::entity::users;
 use crate::entity::users::ActiveModel as UsersActiveModel;
 use sea_orm::entity::prelude::*;
-use sea_orm::{ ActiveValue, FromQueryResult, IntoActiveModel };
+use sea_orm::{ ActiveValue, FromQueryResult, IntoActiveModel, Order };
+use crate::t;
 
 // 创建用户时的请求体
-#[derive(Deserialize)]
+#[derive(Deserialize, ToSchema, Validate)]
 pub struct UserCreateRequest {
+    #[validate(length(min = 1, max = 50))]
     name: String,
+    #[validate(length(min = 6, max = 72))]
     password: String,
     role: Role,
 }
 
 // 修改用户时的请求体
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Debug, ToSchema, Validate)]
 pub struct UserUpdateRequest {
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[validate(length(min = 1, max = 50))]
     name: Option<String>,
 
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -48,58 +60,112 @@ impl UserUpdateRequest {
     }
 }
 
+// 修改密码时的请求体
+#[derive(Deserialize, ToSchema, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct PasswordUpdateRequest {
+    #[validate(length(min = 1))]
+    old_password: String,
+    #[validate(length(min = 6, max = 72))]
+    new_password: String,
+}
+
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct UserQuery {
     #[serde(flatten)]
     pagination: PaginationParams,
+    #[serde(flatten)]
+    list: ListQuery,
+}
+
+impl Queryable for Users {
+    fn fields() -> Vec<QueryField<users::Column>> {
+        vec![
+            QueryField::new("name", users::Column::Name, FieldKind::Text),
+            QueryField::new("role", users::Column::Role, FieldKind::Enum(parse_enum::<Role>)),
+            QueryField::new("created_at", users::Column::CreatedAt, FieldKind::DateTime),
+            QueryField::new("updated_at", users::Column::UpdatedAt, FieldKind::DateTime)
+        ]
+    }
+
+    fn search_columns() -> Vec<users::Column> {
+        vec![users::Column::Name]
+    }
+
+    fn default_sort() -> Vec<(users::Column, Order)> {
+        vec![(users::Column::CreatedAt, Order::Desc), (users::Column::UserId, Order::Desc)]
+    }
 }
 
-#[derive(Debug, Serialize, FromQueryResult)]
+type UserListParams = ListParams<Users>;
+
+#[derive(Debug, Serialize, FromQueryResult, ToSchema)]
 pub struct UserResponse {
     user_id: String,
     name: String,
     role: Role,
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/users",
+    tag = "users",
+    params(PaginationParams, UserListParams),
+    responses((status = 200, description = "用户列表", body = ApiResponse<Page<UserResponse>>))
+)]
 async fn get_all_users(
-    State(AppState { db }): State<AppState>,
-    Query(UserQuery { pagination }): Query<UserQuery>
+    _: Authorized<AdminOnly>,
+    State(AppState { db, hub, .. }): State<AppState>,
+    ValidQuery(UserQuery { pagination, list }): ValidQuery<UserQuery>
 ) -> ApiResult<ApiResponse<Page<UserResponse>>> {
-    let paginator = Users::find()
+    let paginator = list
+        .apply(Users::find())?
         .select_only()
         .column(users::Column::UserId)
         .column(users::Column::Name)
         .column(users::Column::Role)
-        // .order_by_desc(users::Column::CreatedAt)
         .into_model::<UserResponse>()
         .paginate(&db, pagination.size);
 
     let total = paginator.num_items().await?;
 
-    match paginator.fetch_page(pagination.page - 1).await {
+    match paginator.fetch_page(pagination.index()).await {
         Ok(users) => {
             let page = Page::from_pagination(pagination, total, users);
-            LogManager::create_log(&db, LogManager::info(), format!("获取所有用户成功")).await?;
-            Ok(ApiResponse::ok("查找用户成功", Some(page)))
+            LogManager::create_log(&db, &hub, LogManager::info(), "获取所有用户成功".to_string()).await?;
+            Ok(ApiResponse::ok(t!("user.list"), Some(page)))
         }
         Err(e) => {
-            LogManager::create_log(&db, LogManager::error(), format!("获取用户失败")).await?;
-            Err(ApiError::Biz(format!("查找所有用户失败：{}", e)))
+            LogManager::create_log(&db, &hub, LogManager::error(), "获取用户失败".to_string()).await?;
+            Err(e.into())
         }
     }
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/users",
+    tag = "users",
+    request_body = UserCreateRequest,
+    responses((status = 200, description = "创建成功", body = MessageResponse))
+)]
 async fn add_user(
-    State(AppState { db }): State<AppState>,
-    Json(data): Json<UserCreateRequest>
+    Authorized { claims, .. }: Authorized<AdminOnly>,
+    State(AppState { db, hub, .. }): State<AppState>,
+    ValidJson(data): ValidJson<UserCreateRequest>
 ) -> ApiResult<ApiResponse<()>> {
+    if data.role != Role::User && !claims.is_superadmin() {
+        return Err(auth::deny(&db, &hub, &claims, "创建管理员账号").await);
+    }
+
     let user_name = data.name.clone();
+    let hashed = password::hash(&data.password).await?;
 
     let user = UsersActiveModel {
         user_id: ActiveValue::set(xid::new().to_string()),
         name: ActiveValue::set(data.name),
-        password: ActiveValue::set(data.password),
+        password: ActiveValue::set(hashed),
         role: ActiveValue::set(data.role),
         ..Default::default()
     };
@@ -108,26 +174,40 @@ async fn add_user(
         Ok(_) => {
             LogManager::create_log(
                 &db,
+                &hub,
                 LogManager::info(),
                 format!("创建用户<{}>成功", user_name)
             ).await?;
-            Ok(ApiResponse::ok("创建用户成功", None))
+            Ok(ApiResponse::ok(t!("user.created"), None))
         }
         Err(e) => {
             LogManager::create_log(
                 &db,
+                &hub,
                 LogManager::error(),
                 format!("创建用户<{}>失败: {}", user_name, e)
             ).await?;
-            Err(ApiError::Biz(format!("创建用户失败: {}", e)))
+            Err(e.into())
         }
     }
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/users/{id}",
+    tag = "users",
+    params(("id" = String, Path, description = "用户 ID")),
+    responses((status = 200, description = "用户详情", body = ApiResponse<UserResponse>))
+)]
 async fn get_user(
-    State(AppState { db }): State<AppState>,
-    Path(id): Path<String>
+    claims: Claims,
+    State(AppState { db, hub, .. }): State<AppState>,
+    ValidPath(id): ValidPath<String>
 ) -> ApiResult<ApiResponse<UserResponse>> {
+    if !claims.is_self_or_admin(&id) {
+        return Err(auth::deny(&db, &hub, &claims, &format!("查看用户<{}>", id)).await);
+    }
+
     let user = Users::find()
         .select_only()
         .column(users::Column::UserId)
@@ -135,77 +215,163 @@ async fn get_user(
         .column(users::Column::Role)
         .filter(users::Column::UserId.eq(&id))
         .into_model::<UserResponse>()
-        .one(&db).await
-        .unwrap();
+        .one(&db).await?;
 
     if let Some(user) = user {
-        LogManager::create_log(&db, LogManager::info(), format!("获取用户<{}>", user.name)).await?;
-        Ok(ApiResponse::ok("获取用户成功", Some(user)))
+        LogManager::create_log(&db, &hub, LogManager::info(), format!("获取用户<{}>", user.name)).await?;
+        Ok(ApiResponse::ok(t!("user.detail"), Some(user)))
     } else {
-        LogManager::create_log(&db, LogManager::info(), format!("未找到该用户<{}>", id)).await?;
-        Err(ApiError::Biz("未找到该用户".to_string()))
+        LogManager::create_log(&db, &hub, LogManager::info(), format!("未找到该用户<{}>", id)).await?;
+        Err(ApiError::NotFound(t!("user.not_found")))
     }
 }
 
+#[utoipa::path(
+    delete,
+    path = "/api/users/{id}",
+    tag = "users",
+    params(("id" = String, Path, description = "用户 ID")),
+    responses((status = 200, description = "删除成功", body = MessageResponse))
+)]
 async fn delete_user(
-    State(AppState { db }): State<AppState>,
-    Path(id): Path<String>
+    Authorized { claims, .. }: Authorized<AdminOnly>,
+    State(AppState { db, hub, .. }): State<AppState>,
+    ValidPath(id): ValidPath<String>
 ) -> ApiResult<ApiResponse<()>> {
-    let user = Users::find_by_id(&id).one(&db).await.unwrap();
+    let user = Users::find_by_id(&id).one(&db).await?;
 
     if let Some(user) = user {
+        if user.role != Role::User && !claims.is_superadmin() {
+            return Err(auth::deny(&db, &hub, &claims, &format!("删除管理员<{}>", user.name)).await);
+        }
+
         let user_name = user.name.clone();
 
-        user.delete(&db).await.unwrap();
+        user.delete(&db).await?;
 
-        LogManager::create_log(&db, LogManager::info(), format!("删除用户<{}>", user_name)).await?;
-        Ok(ApiResponse::ok("删除用户成功", None))
+        LogManager::create_log(&db, &hub, LogManager::info(), format!("删除用户<{}>", user_name)).await?;
+        Ok(ApiResponse::ok(t!("user.deleted"), None))
     } else {
-        LogManager::create_log(&db, LogManager::info(), format!("未找到该用户<{}>", id)).await?;
-        Err(ApiError::Biz("未找到该用户".to_string()))
+        LogManager::create_log(&db, &hub, LogManager::info(), format!("未找到该用户<{}>", id)).await?;
+        Err(ApiError::NotFound(t!("user.not_found")))
     }
 }
 
+#[utoipa::path(
+    put,
+    path = "/api/users/{id}",
+    tag = "users",
+    params(("id" = String, Path, description = "用户 ID")),
+    request_body = UserUpdateRequest,
+    responses((status = 200, description = "更新成功", body = MessageResponse))
+)]
 async fn update_user(
-    State(AppState { db }): State<AppState>,
-    Path(id): Path<String>,
-    Json(data): Json<UserUpdateRequest>
+    claims: Claims,
+    State(AppState { db, hub, .. }): State<AppState>,
+    ValidPath(id): ValidPath<String>,
+    ValidJson(data): ValidJson<UserUpdateRequest>
 ) -> ApiResult<ApiResponse<()>> {
-    let user = Users::find_by_id(&id).one(&db).await.unwrap();
+    if !claims.is_self_or_admin(&id) {
+        return Err(auth::deny(&db, &hub, &claims, &format!("修改用户<{}>", id)).await);
+    }
+    if data.role.is_some() && !claims.is_superadmin() {
+        return Err(auth::deny(&db, &hub, &claims, &format!("修改用户<{}>的角色", id)).await);
+    }
+
+    let user = Users::find_by_id(&id).one(&db).await?;
 
     if let Some(user) = user {
+        // 与删除一致，只有超级管理员可以修改其他管理员
+        if user.role != Role::User && claims.user_id != id && !claims.is_superadmin() {
+            return Err(auth::deny(&db, &hub, &claims, &format!("修改管理员<{}>", user.name)).await);
+        }
+
         let mut user = user.into_active_model();
 
         data.apply_to(&mut user);
 
-        user.update(&db).await.unwrap();
+        user.update(&db).await?;
 
         LogManager::create_log(
             &db,
+            &hub,
             LogManager::info(),
             format!("更新用户参数为：{:?}", data)
         ).await?;
 
-        Ok(ApiResponse::ok("更新用户成功", None))
+        Ok(ApiResponse::ok(t!("user.updated"), None))
     } else {
-        LogManager::create_log(&db, LogManager::info(), format!("未找到该用户<{}>", id)).await?;
-        Err(ApiError::Biz("未找到该用户".to_string()))
+        LogManager::create_log(&db, &hub, LogManager::info(), format!("未找到该用户<{}>", id)).await?;
+        Err(ApiError::NotFound(t!("user.not_found")))
+    }
+}
+#[utoipa::path(
+    put,
+    path = "/api/users/{id}/password",
+    tag = "users",
+    params(("id" = String, Path, description = "用户 ID")),
+    request_body = PasswordUpdateRequest,
+    responses((status = 200, description = "修改成功", body = MessageResponse))
+)]
+async fn update_password(
+    claims: Claims,
+    State(AppState { db, hub, .. }): State<AppState>,
+    ValidPath(id): ValidPath<String>,
+    ValidJson(data): ValidJson<PasswordUpdateRequest>
+) -> ApiResult<ApiResponse<()>> {
+    if claims.user_id != id {
+        return Err(auth::deny(&db, &hub, &claims, &format!("修改用户<{}>的密码", id)).await);
+    }
+
+    let user = Users::find_by_id(&id).one(&db).await?;
+
+    let Some(user) = user else {
+        LogManager::create_log(&db, &hub, LogManager::info(), format!("未找到该用户<{}>", id)).await?;
+        return Err(ApiError::NotFound(t!("user.not_found")));
+    };
+
+    if !password::verify(&data.old_password, &user.password).await {
+        LogManager::create_log(
+            &db,
+            &hub,
+            LogManager::warning(),
+            format!("用户<{}>修改密码失败: 原密码错误", user.name)
+        ).await?;
+        return Err(ApiError::BadRequest(t!("user.wrong_password")));
     }
+
+    let user_name = user.name.clone();
+    let hashed = password::hash(&data.new_password).await?;
+
+    let mut user = user.into_active_model();
+    user.password = ActiveValue::set(hashed);
+    user.update(&db).await?;
+
+    LogManager::create_log(&db, &hub, LogManager::info(), format!("用户<{}>修改密码成功", user_name)).await?;
+    Ok(ApiResponse::ok(t!("user.password_updated"), None))
 }
-#[derive(Debug, Serialize, FromQueryResult)]
+
+#[derive(Debug, Serialize, FromQueryResult, ToSchema)]
 struct UserCategoryItem {
     role: String,
     count: i64,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 struct UserCategoryResponse {
     categories: Vec<UserCategoryItem>,
 }
 
-async fn get_user_category(State(
-    AppState { db },
-): State<AppState>) -> ApiResult<ApiResponse<UserCategoryResponse>> {
+#[utoipa::path(
+    get,
+    path = "/api/users/category",
+    tag = "users",
+    responses((status = 200, description = "按角色统计的用户数量", body = ApiResponse<UserCategoryResponse>))
+)]
+async fn get_user_category(
+    _: Authorized<AdminOnly>,
+    State(AppState { db, .. }): State<AppState>
+) -> ApiResult<ApiResponse<UserCategoryResponse>> {
     let result = Users::find()
         .select_only()
         .column(users::Column::Role)
@@ -214,9 +380,24 @@ async fn get_user_category(State(
         .into_model::<UserCategoryItem>()
         .all(&db).await?;
 
-    Ok(ApiResponse::ok("获取用户分布成功", Some(UserCategoryResponse { categories: result })))
+    Ok(ApiResponse::ok(t!("user.categories"), Some(UserCategoryResponse { categories: result })))
 }
 
+#[derive(OpenApi)]
+#[openapi(
+    paths(
+        get_all_users,
+        add_user,
+        get_user,
+        update_user,
+        delete_user,
+        update_password,
+        get_user_category
+    ),
+    tags((name = "users", description = "用户管理"))
+)]
+pub struct UserApi;
+
 pub fn create_user_router() -> Router<AppState> {
     Router::new()
         .route("/", get(get_all_users))
@@ -224,5 +405,6 @@ pub fn create_user_router() -> Router<AppState> {
         .route("/{id}", get(get_user))
         .route("/{id}", put(update_user))
         .route("/{id}", delete(delete_user))
+        .route("/{id}/password", put(update_password))
         .route("/category", get(get_user_category))
 }
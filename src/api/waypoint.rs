@@ -0,0 +1,411 @@
+use axum::extract::State;
+use axum::Router;
+use axum::routing::{ get, put };
+use sea_orm::entity::prelude::*;
+use sea_orm::{ ActiveValue, DatabaseTransaction, IntoActiveModel, QueryFilter, QueryOrder, QuerySelect, TransactionTrait };
+use serde::{ Deserialize, Serialize };
+use utoipa::{ IntoParams, OpenApi, ToSchema };
+use validator::Validate;
+
+use crate::app::AppState;
+use crate::common::auth;
+use crate::common::geo;
+use crate::common::jwt::Claims;
+use crate::common::response::ApiResponse;
+use crate::common::result::{ ApiError, ApiResult };
+use crate::common::validation::{ self, ValidJson, ValidPath, ValidQuery };
+use crate::entity::mission_waypoints::ActiveModel as WaypointsActiveModel;
+use crate::entity::prelude::{ MissionWaypoints, Missions };
+use crate::entity::sea_orm_active_enums::{ Status, WaypointAction };
+use crate::entity::{ mission_waypoints, missions };
+use crate::hub::Hub;
+use crate::t;
+
+// 未指定时估算航时使用的巡航速度（米/秒）
+const DEFAULT_CRUISE_SPEED: f64 = 10.0;
+
+// 单个任务允许的最大航点数
+pub(super) const MAX_WAYPOINTS: u64 = 500;
+
+#[derive(Debug, Serialize, Deserialize, ToSchema, Validate)]
+#[serde(rename_all = "camelCase")]
+pub(super) struct WaypointRequest {
+    /// 插入位置（从 1 开始），不指定或超出范围时追加到末尾；整体替换时按数组顺序编号，忽略该字段
+    #[validate(range(min = 1))]
+    pub(super) seq: Option<i32>,
+    #[validate(custom(function = "validation::latitude"))]
+    pub(super) lat: Decimal,
+    #[validate(custom(function = "validation::longitude"))]
+    pub(super) lng: Decimal,
+    /// 相对起飞点高度（米）
+    #[validate(range(min = 0.0, max = 10000.0))]
+    pub(super) altitude: f32,
+    /// 到达后悬停的秒数
+    #[serde(default)]
+    #[validate(range(min = 0, max = 3600))]
+    pub(super) loiter_seconds: i32,
+    #[serde(default = "default_action")]
+    pub(super) action: WaypointAction,
+}
+
+fn default_action() -> WaypointAction {
+    WaypointAction::FlyThrough
+}
+
+#[derive(Debug, Deserialize, ToSchema, Validate)]
+#[serde(rename_all = "camelCase")]
+struct WaypointUpdateRequest {
+    /// 移动到的位置（从 1 开始），超出范围时移动到末尾
+    #[validate(range(min = 1))]
+    seq: Option<i32>,
+    #[validate(custom(function = "validation::latitude"))]
+    lat: Option<Decimal>,
+    #[validate(custom(function = "validation::longitude"))]
+    lng: Option<Decimal>,
+    #[validate(range(min = 0.0, max = 10000.0))]
+    altitude: Option<f32>,
+    #[validate(range(min = 0, max = 3600))]
+    loiter_seconds: Option<i32>,
+    action: Option<WaypointAction>,
+}
+
+impl WaypointUpdateRequest {
+    fn apply_to(self, waypoint: &mut WaypointsActiveModel) {
+        if let Some(lat) = self.lat {
+            waypoint.lat = ActiveValue::set(lat);
+        }
+        if let Some(lng) = self.lng {
+            waypoint.lng = ActiveValue::set(lng);
+        }
+        if let Some(altitude) = self.altitude {
+            waypoint.altitude = ActiveValue::set(altitude);
+        }
+        if let Some(loiter_seconds) = self.loiter_seconds {
+            waypoint.loiter_seconds = ActiveValue::set(loiter_seconds);
+        }
+        if let Some(action) = self.action {
+            waypoint.action = ActiveValue::set(action);
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, ToSchema, Validate)]
+struct WaypointReplaceRequest {
+    /// 按数组顺序重新编号的完整航点列表
+    #[validate(length(max = MAX_WAYPOINTS), nested)]
+    waypoints: Vec<WaypointRequest>,
+}
+
+#[derive(Debug, Deserialize, IntoParams, Validate)]
+#[into_params(parameter_in = Query)]
+struct RouteParams {
+    /// 估算航时使用的巡航速度（米/秒），默认 10
+    #[validate(range(exclusive_min = 0.0, max = 50.0))]
+    speed: Option<f64>,
+}
+
+// distance 为依次飞过各航点的总航程（米），duration 为按巡航速度飞行加悬停的预计耗时（秒）
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+struct WaypointRoute {
+    waypoints: Vec<mission_waypoints::Model>,
+    distance: f64,
+    cruise_speed: f64,
+    duration: f64,
+}
+
+impl WaypointRoute {
+    fn new(waypoints: Vec<mission_waypoints::Model>, cruise_speed: f64) -> Self {
+        // 航段长度计入高度差
+        let distance: f64 = waypoints
+            .windows(2)
+            .map(|leg| {
+                let (from, to) = (&leg[0], &leg[1]);
+                let horizontal = geo::haversine(
+                    geo::to_f64(&from.lat),
+                    geo::to_f64(&from.lng),
+                    geo::to_f64(&to.lat),
+                    geo::to_f64(&to.lng)
+                );
+                horizontal.hypot(f64::from(to.altitude - from.altitude))
+            })
+            .sum();
+        let loiter: i64 = waypoints
+            .iter()
+            .map(|waypoint| i64::from(waypoint.loiter_seconds))
+            .sum();
+
+        WaypointRoute {
+            waypoints,
+            distance,
+            cruise_speed,
+            duration: distance / cruise_speed + loiter as f64,
+        }
+    }
+}
+
+async fn find_mission(
+    db: &DatabaseConnection,
+    hub: &Hub,
+    claims: &Claims,
+    id: &str
+) -> ApiResult<missions::Model> {
+    let Some(mission) = Missions::find_by_id(id).one(db).await? else {
+        return Err(ApiError::NotFound(t!("mission.not_found", id = id)));
+    };
+    if !claims.is_self_or_admin(&mission.user_id) {
+        return Err(auth::deny(db, hub, claims, &format!("访问任务<{}>的航点", id)).await);
+    }
+    Ok(mission)
+}
+
+// 只有未开始的任务可以修改航点；在事务中锁定任务行，避免与开始任务并发
+async fn lock_mission(
+    db: &DatabaseConnection,
+    hub: &Hub,
+    claims: &Claims,
+    id: &str
+) -> ApiResult<DatabaseTransaction> {
+    let txn = db.begin().await?;
+
+    let Some(mission) = Missions::find_by_id(id).lock_exclusive().one(&txn).await? else {
+        return Err(ApiError::NotFound(t!("mission.not_found", id = id)));
+    };
+    if !claims.is_self_or_admin(&mission.user_id) {
+        return Err(auth::deny(db, hub, claims, &format!("修改任务<{}>的航点", id)).await);
+    }
+    if mission.status != Status::Idle {
+        return Err(
+            ApiError::Conflict(
+                t!("waypoint.locked", id = id, status = format!("{:?}", mission.status))
+            )
+        );
+    }
+
+    Ok(txn)
+}
+
+pub(super) async fn load_waypoints<C: ConnectionTrait>(
+    conn: &C,
+    mission_id: &str
+) -> ApiResult<Vec<mission_waypoints::Model>> {
+    Ok(
+        MissionWaypoints::find()
+            .filter(mission_waypoints::Column::MissionId.eq(mission_id))
+            .order_by_asc(mission_waypoints::Column::Seq)
+            .order_by_asc(mission_waypoints::Column::WaypointId)
+            .all(conn).await?
+    )
+}
+
+// 按列表顺序把序号重排为 1..n，gap 位置留给正在插入或移动的航点；只更新序号发生变化的航点
+async fn resequence(
+    txn: &DatabaseTransaction,
+    waypoints: Vec<mission_waypoints::Model>,
+    gap: Option<usize>
+) -> ApiResult<()> {
+    for (index, waypoint) in waypoints.into_iter().enumerate() {
+        let skipped = gap.is_some_and(|gap| index >= gap);
+        let seq = (index as i32) + if skipped { 2 } else { 1 };
+        if waypoint.seq != seq {
+            let mut waypoint = waypoint.into_active_model();
+            waypoint.seq = ActiveValue::set(seq);
+            waypoint.update(txn).await?;
+        }
+    }
+    Ok(())
+}
+
+// 把从 1 开始的位置换算为列表下标，超出范围时取末尾
+fn position(seq: Option<i32>, len: usize) -> usize {
+    seq.and_then(|seq| usize::try_from(seq - 1).ok()).map_or(len, |index| index.min(len))
+}
+
+fn active_model(mission_id: &str, seq: i32, data: WaypointRequest) -> WaypointsActiveModel {
+    WaypointsActiveModel {
+        mission_id: ActiveValue::set(mission_id.to_owned()),
+        seq: ActiveValue::set(seq),
+        lat: ActiveValue::set(data.lat),
+        lng: ActiveValue::set(data.lng),
+        altitude: ActiveValue::set(data.altitude),
+        loiter_seconds: ActiveValue::set(data.loiter_seconds),
+        action: ActiveValue::set(data.action),
+        ..Default::default()
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/missions/{id}/waypoints",
+    tag = "missions",
+    params(("id" = String, Path, description = "任务 ID"), RouteParams),
+    responses((status = 200, description = "按顺序排列的航点及航程、预计航时", body = ApiResponse<WaypointRoute>))
+)]
+async fn get_waypoints(
+    claims: Claims,
+    State(AppState { db, hub, .. }): State<AppState>,
+    ValidPath(id): ValidPath<String>,
+    ValidQuery(params): ValidQuery<RouteParams>
+) -> ApiResult<ApiResponse<WaypointRoute>> {
+    params.validate().map_err(ApiError::Validation)?;
+
+    find_mission(&db, &hub, &claims, &id).await?;
+    let waypoints = load_waypoints(&db, &id).await?;
+
+    let route = WaypointRoute::new(waypoints, params.speed.unwrap_or(DEFAULT_CRUISE_SPEED));
+    Ok(ApiResponse::ok(t!("waypoint.list"), Some(route)))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/missions/{id}/waypoints",
+    tag = "missions",
+    params(("id" = String, Path, description = "任务 ID")),
+    request_body = WaypointRequest,
+    responses(
+        (status = 200, description = "添加成功", body = ApiResponse<mission_waypoints::Model>),
+        (status = 409, description = "任务已开始，航点不可修改")
+    )
+)]
+async fn add_waypoint(
+    claims: Claims,
+    State(AppState { db, hub, .. }): State<AppState>,
+    ValidPath(id): ValidPath<String>,
+    ValidJson(data): ValidJson<WaypointRequest>
+) -> ApiResult<ApiResponse<mission_waypoints::Model>> {
+    let txn = lock_mission(&db, &hub, &claims, &id).await?;
+
+    let waypoints = load_waypoints(&txn, &id).await?;
+    if waypoints.len() as u64 >= MAX_WAYPOINTS {
+        return Err(ApiError::Conflict(t!("waypoint.too_many", max = MAX_WAYPOINTS)));
+    }
+
+    // 先腾出插入位置，再插入新航点
+    let index = position(data.seq, waypoints.len());
+    resequence(&txn, waypoints, Some(index)).await?;
+
+    let waypoint = active_model(&id, (index as i32) + 1, data).insert(&txn).await?;
+    txn.commit().await?;
+
+    Ok(ApiResponse::ok(t!("waypoint.created"), Some(waypoint)))
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/missions/{id}/waypoints",
+    tag = "missions",
+    params(("id" = String, Path, description = "任务 ID")),
+    request_body = WaypointReplaceRequest,
+    responses(
+        (status = 200, description = "替换成功", body = ApiResponse<WaypointRoute>),
+        (status = 409, description = "任务已开始，航点不可修改")
+    )
+)]
+async fn replace_waypoints(
+    claims: Claims,
+    State(AppState { db, hub, .. }): State<AppState>,
+    ValidPath(id): ValidPath<String>,
+    ValidJson(data): ValidJson<WaypointReplaceRequest>
+) -> ApiResult<ApiResponse<WaypointRoute>> {
+    let txn = lock_mission(&db, &hub, &claims, &id).await?;
+
+    MissionWaypoints::delete_many()
+        .filter(mission_waypoints::Column::MissionId.eq(&id))
+        .exec(&txn).await?;
+
+    let mut waypoints = Vec::with_capacity(data.waypoints.len());
+    for (seq, waypoint) in (1..).zip(data.waypoints) {
+        waypoints.push(active_model(&id, seq, waypoint).insert(&txn).await?);
+    }
+    txn.commit().await?;
+
+    let route = WaypointRoute::new(waypoints, DEFAULT_CRUISE_SPEED);
+    Ok(ApiResponse::ok(t!("waypoint.replaced"), Some(route)))
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/missions/{id}/waypoints/{waypoint_id}",
+    tag = "missions",
+    params(
+        ("id" = String, Path, description = "任务 ID"),
+        ("waypoint_id" = i64, Path, description = "航点 ID")
+    ),
+    request_body = WaypointUpdateRequest,
+    responses(
+        (status = 200, description = "更新成功", body = ApiResponse<mission_waypoints::Model>),
+        (status = 409, description = "任务已开始，航点不可修改")
+    )
+)]
+async fn update_waypoint(
+    claims: Claims,
+    State(AppState { db, hub, .. }): State<AppState>,
+    ValidPath((id, waypoint_id)): ValidPath<(String, i64)>,
+    ValidJson(data): ValidJson<WaypointUpdateRequest>
+) -> ApiResult<ApiResponse<mission_waypoints::Model>> {
+    let txn = lock_mission(&db, &hub, &claims, &id).await?;
+
+    let mut waypoints = load_waypoints(&txn, &id).await?;
+    let Some(index) = waypoints.iter().position(|waypoint| waypoint.waypoint_id == waypoint_id) else {
+        return Err(ApiError::NotFound(t!("waypoint.not_found", id = waypoint_id)));
+    };
+
+    // 不调整顺序时留在原位置，其余航点按列表顺序重排
+    let waypoint = waypoints.remove(index);
+    let target = data.seq.map_or(index, |seq| position(Some(seq), waypoints.len()));
+    let seq = (target as i32) + 1;
+    resequence(&txn, waypoints, Some(target)).await?;
+
+    let mut waypoint = waypoint.into_active_model();
+    waypoint.seq = ActiveValue::set(seq);
+    data.apply_to(&mut waypoint);
+    let waypoint = waypoint.update(&txn).await?;
+    txn.commit().await?;
+
+    Ok(ApiResponse::ok(t!("waypoint.updated"), Some(waypoint)))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/missions/{id}/waypoints/{waypoint_id}",
+    tag = "missions",
+    params(
+        ("id" = String, Path, description = "任务 ID"),
+        ("waypoint_id" = i64, Path, description = "航点 ID")
+    ),
+    responses(
+        (status = 200, description = "删除成功", body = ApiResponse<WaypointRoute>),
+        (status = 409, description = "任务已开始，航点不可修改")
+    )
+)]
+async fn delete_waypoint(
+    claims: Claims,
+    State(AppState { db, hub, .. }): State<AppState>,
+    ValidPath((id, waypoint_id)): ValidPath<(String, i64)>
+) -> ApiResult<ApiResponse<WaypointRoute>> {
+    let txn = lock_mission(&db, &hub, &claims, &id).await?;
+
+    let mut waypoints = load_waypoints(&txn, &id).await?;
+    let Some(index) = waypoints.iter().position(|waypoint| waypoint.waypoint_id == waypoint_id) else {
+        return Err(ApiError::NotFound(t!("waypoint.not_found", id = waypoint_id)));
+    };
+
+    waypoints.remove(index).delete(&txn).await?;
+    resequence(&txn, waypoints, None).await?;
+
+    let waypoints = load_waypoints(&txn, &id).await?;
+    txn.commit().await?;
+
+    let route = WaypointRoute::new(waypoints, DEFAULT_CRUISE_SPEED);
+    Ok(ApiResponse::ok(t!("waypoint.deleted"), Some(route)))
+}
+
+#[derive(OpenApi)]
+#[openapi(paths(get_waypoints, add_waypoint, replace_waypoints, update_waypoint, delete_waypoint))]
+pub struct WaypointApi;
+
+pub fn create_waypoint_router() -> Router<AppState> {
+    Router::new()
+        .route("/{id}/waypoints", get(get_waypoints).post(add_waypoint).put(replace_waypoints))
+        .route("/{id}/waypoints/{waypoint_id}", put(update_waypoint).delete(delete_waypoint))
+}
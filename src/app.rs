@@ -1,16 +1,19 @@
+use std::sync::Arc;
+
 use sea_orm::DatabaseConnection;
 
+use crate::common::AppConfig;
+use crate::hub::Hub;
+
 #[derive(Debug, Clone)]
 pub struct AppState {
     pub db: DatabaseConnection,
+    pub config: Arc<AppConfig>,
+    pub hub: Hub,
 }
 
 impl AppState {
-    pub fn new(db: DatabaseConnection) -> Self {
-        AppState { db }
+    pub fn new(db: DatabaseConnection, config: AppConfig) -> Self {
+        AppState { db, config: Arc::new(config), hub: Hub::new() }
     }
-
-    // pub fn db(&self) -> &DatabaseConnection {
-    //     &self.db
-    // }
 }
@@ -0,0 +1,62 @@
+use anyhow::{ Context, bail };
+use sea_orm::entity::prelude::*;
+use sea_orm::{ ActiveValue, IntoActiveModel };
+
+use crate::common::password;
+use crate::entity::prelude::Users;
+use crate::entity::sea_orm_active_enums::Role;
+use crate::entity::users::ActiveModel as UsersActiveModel;
+
+const REHASH_PASSWORDS: &str = "rehash-passwords";
+const CREATE_SUPERADMIN: &str = "create-superadmin";
+
+pub async fn run(command: &str, args: &[String], db: &DatabaseConnection) -> anyhow::Result<()> {
+    match command {
+        REHASH_PASSWORDS => rehash_passwords(db).await,
+        CREATE_SUPERADMIN => {
+            let [name, password] = args else {
+                bail!("用法: {} <用户名> <密码>", CREATE_SUPERADMIN);
+            };
+            create_superadmin(db, name, password).await
+        }
+        _ => bail!("未知命令: {}", command),
+    }
+}
+
+// 创建超级管理员，用于空库初始化后的首次登录
+async fn create_superadmin(db: &DatabaseConnection, name: &str, password: &str) -> anyhow::Result<()> {
+    let user = UsersActiveModel {
+        user_id: ActiveValue::set(xid::new().to_string()),
+        name: ActiveValue::set(name.to_string()),
+        password: ActiveValue::set(password::hash(password).await?),
+        role: ActiveValue::set(Role::Superadmin),
+        ..Default::default()
+    };
+
+    user.insert(db).await.context("创建超级管理员失败")?;
+
+    tracing::info!("已创建超级管理员<{}>", name);
+    Ok(())
+}
+
+// 将库中仍为明文的密码重新以 bcrypt 哈希保存，已哈希的记录保持不变
+async fn rehash_passwords(db: &DatabaseConnection) -> anyhow::Result<()> {
+    let users = Users::find().all(db).await?;
+    let mut rehashed = 0;
+
+    for user in users {
+        if password::is_hashed(&user.password) {
+            continue;
+        }
+
+        let hashed = password::hash(&user.password).await?;
+        let mut user = user.into_active_model();
+        user.password = ActiveValue::set(hashed);
+        user.update(db).await?;
+
+        rehashed += 1;
+    }
+
+    tracing::info!("已重新哈希 {} 个用户密码", rehashed);
+    Ok(())
+}
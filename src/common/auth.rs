@@ -0,0 +1,140 @@
+use std::marker::PhantomData;
+
+use axum::body::Body;
+use axum::extract::FromRequestParts;
+use axum::http::header::AUTHORIZATION;
+use axum::http::request::Parts;
+use axum::http::{ HeaderMap, Request, Response };
+use axum::response::IntoResponse;
+use tower_http::validate_request::ValidateRequest;
+
+use sea_orm::DatabaseConnection;
+
+use super::config::JwtConfig;
+use super::jwt::{ self, Claims };
+use super::result::ApiError;
+use crate::api::logs::LogManager;
+use crate::app::AppState;
+use crate::hub::Hub;
+use crate::entity::sea_orm_active_enums::Role;
+use crate::t;
+
+const BEARER_PREFIX: &str = "Bearer ";
+
+// 从 Authorization 请求头中取出 Bearer 令牌
+pub fn bearer_token(headers: &HeaderMap) -> Option<&str> {
+    headers
+        .get(AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix(BEARER_PREFIX))
+}
+
+// 校验请求头中的 Bearer 令牌，并将解析出的 Claims 放入请求扩展
+#[derive(Debug, Clone)]
+pub struct JwtAuth {
+    config: JwtConfig,
+}
+
+impl JwtAuth {
+    pub fn new(config: JwtConfig) -> Self {
+        JwtAuth { config }
+    }
+}
+
+impl<B> ValidateRequest<B> for JwtAuth {
+    type ResponseBody = Body;
+
+    fn validate(&mut self, request: &mut Request<B>) -> Result<(), Response<Self::ResponseBody>> {
+        let Some(token) = bearer_token(request.headers()) else {
+            return Err(ApiError::Unauthorized(t!("auth.token_missing")).into_response());
+        };
+
+        match jwt::decode_token(&self.config, token) {
+            Ok(claims) => {
+                request.extensions_mut().insert(claims);
+                Ok(())
+            }
+            Err(_) => Err(ApiError::Unauthorized(t!("auth.token_invalid")).into_response()),
+        }
+    }
+}
+
+impl<S: Send + Sync> FromRequestParts<S> for Claims {
+    type Rejection = ApiError;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        parts.extensions
+            .get::<Claims>()
+            .cloned()
+            .ok_or_else(|| ApiError::Unauthorized(t!("auth.token_missing")))
+    }
+}
+
+fn rank(role: &Role) -> u8 {
+    match role {
+        Role::Superadmin => 2,
+        Role::Admin => 1,
+        Role::User => 0,
+    }
+}
+
+impl Claims {
+    pub fn is_admin(&self) -> bool {
+        rank(&self.role) >= rank(&Role::Admin)
+    }
+
+    pub fn is_superadmin(&self) -> bool {
+        rank(&self.role) >= rank(&Role::Superadmin)
+    }
+
+    // 本人或管理员
+    pub fn is_self_or_admin(&self, user_id: &str) -> bool {
+        self.user_id == user_id || self.is_admin()
+    }
+}
+
+// 路由访问策略，声明访问该路由所需的最低角色
+pub trait Policy {
+    const MIN_ROLE: Role;
+}
+
+pub struct AdminOnly;
+
+impl Policy for AdminOnly {
+    const MIN_ROLE: Role = Role::Admin;
+}
+
+// 记录越权访问并返回 403
+pub async fn deny(db: &DatabaseConnection, hub: &Hub, claims: &Claims, action: &str) -> ApiError {
+    let message = format!("用户<{}>({:?})无权执行: {}", claims.user_id, claims.role, action);
+
+    if let Err(e) = LogManager::create_log(db, hub, LogManager::warning(), message).await {
+        tracing::error!("记录越权访问日志失败: {}", e);
+    }
+
+    ApiError::Forbidden(t!("auth.forbidden"))
+}
+
+// 按策略校验当前用户角色的提取器
+pub struct Authorized<P> {
+    pub claims: Claims,
+    _policy: PhantomData<P>,
+}
+
+impl<P: Policy + Send + Sync> FromRequestParts<AppState> for Authorized<P> {
+    type Rejection = ApiError;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &AppState
+    ) -> Result<Self, Self::Rejection> {
+        let claims = Claims::from_request_parts(parts, state).await?;
+
+        if rank(&claims.role) < rank(&P::MIN_ROLE) {
+            let action = format!("{} {}", parts.method, parts.uri.path());
+            return Err(deny(&state.db, &state.hub, &claims, &action).await);
+        }
+
+        Ok(Authorized { claims, _policy: PhantomData })
+    }
+}
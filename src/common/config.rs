@@ -0,0 +1,160 @@
+use anyhow::{ Context, bail, ensure };
+use axum::http::HeaderValue;
+use config::{ Config, Environment, File };
+use serde::Deserialize;
+use tracing_subscriber::EnvFilter;
+
+use super::server::ServerConfig;
+
+// 配置文件路径（不含扩展名）
+const CONFIG_FILE: &str = "config/app";
+
+// 环境变量前缀
+const ENV_PREFIX: &str = "APP";
+
+// config/app.yaml 中自带的 JWT 密钥占位值，部署时必须替换
+const PLACEHOLDER_JWT_SECRET: &str = "please-change-this-jwt-secret";
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct DatabaseConfig {
+    pub url: String,
+    pub max_connections: u32,
+    pub min_connections: u32,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct LogConfig {
+    pub level: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct CorsConfig {
+    pub origins: Vec<String>,
+}
+
+impl CorsConfig {
+    pub fn allow_any(&self) -> bool {
+        self.origins.iter().any(|origin| origin == "*")
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct JwtConfig {
+    pub secret: String,
+    pub expires_in: u64,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ApiConfig {
+    // 为 true 时错误按旧格式返回：code 为 0，业务错误的 HTTP 状态码为 200
+    pub legacy_errors: bool,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct SchedulerConfig {
+    // 多实例部署时只应在一个实例上开启
+    pub enabled: bool,
+    // 检查到期任务模板的间隔（秒）
+    pub interval: u64,
+}
+
+// 机体保养间隔，任一项超出即提示需要保养；设为 0 表示不按该项检查
+#[derive(Debug, Clone, Deserialize)]
+pub struct MaintenanceConfig {
+    // 距上次保养的飞行小时数
+    pub service_hours: f64,
+    // 距上次保养的起降次数
+    pub service_cycles: u64,
+    // 距上次保养的天数
+    pub service_days: u64,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct AppConfig {
+    pub server: ServerConfig,
+    pub database: DatabaseConfig,
+    pub log: LogConfig,
+    pub cors: CorsConfig,
+    pub jwt: JwtConfig,
+    pub api: ApiConfig,
+    pub scheduler: SchedulerConfig,
+    pub maintenance: MaintenanceConfig,
+}
+
+impl AppConfig {
+    pub fn url(&self) -> String {
+        format!("{}:{}", self.server.host, self.server.port)
+    }
+
+    // 依次读取默认值、配置文件和环境变量，后者覆盖前者
+    pub fn load() -> anyhow::Result<Self> {
+        let config = Config::builder()
+            .set_default("server.host", "0.0.0.0")?
+            .set_default("server.port", 5001)?
+            .set_default("database.max_connections", 10)?
+            .set_default("database.min_connections", 1)?
+            .set_default("log.level", "info")?
+            .set_default("cors.origins", vec!["*"])?
+            .set_default("jwt.expires_in", 7200)?
+            .set_default("api.legacy_errors", false)?
+            .set_default("scheduler.enabled", true)?
+            .set_default("scheduler.interval", 30)?
+            .set_default("maintenance.service_hours", 50.0)?
+            .set_default("maintenance.service_cycles", 200)?
+            .set_default("maintenance.service_days", 180)?
+            .add_source(File::with_name(CONFIG_FILE).required(false))
+            .add_source(
+                Environment::with_prefix(ENV_PREFIX)
+                    .prefix_separator("_")
+                    .separator("__")
+                    .list_separator(",")
+                    .with_list_parse_key("cors.origins")
+                    .try_parsing(true)
+            )
+            .build()
+            .context("读取配置失败")?;
+
+        let config: AppConfig = config.try_deserialize().context("解析配置失败")?;
+        config.validate()?;
+
+        Ok(config)
+    }
+
+    fn validate(&self) -> anyhow::Result<()> {
+        ensure!(!self.server.host.is_empty(), "server.host 不能为空");
+        ensure!(self.server.port != 0, "server.port 不能为 0");
+
+        let url = &self.database.url;
+        if !url.starts_with("mysql://") && !url.starts_with("sqlite:") {
+            bail!("database.url 仅支持 mysql:// 或 sqlite: 协议");
+        }
+        ensure!(self.database.max_connections > 0, "database.max_connections 必须大于 0");
+        ensure!(
+            self.database.min_connections <= self.database.max_connections,
+            "database.min_connections 不能大于 database.max_connections"
+        );
+
+        EnvFilter::try_new(&self.log.level).with_context(||
+            format!("log.level 无效: {}", self.log.level)
+        )?;
+
+        ensure!(!self.cors.origins.is_empty(), "cors.origins 不能为空");
+        for origin in self.cors.origins.iter().filter(|origin| *origin != "*") {
+            HeaderValue::from_str(origin).with_context(|| format!("cors.origins 无效: {}", origin))?;
+        }
+
+        ensure!(
+            self.jwt.secret != PLACEHOLDER_JWT_SECRET,
+            "jwt.secret 仍是默认占位值，请在配置文件或环境变量 APP_JWT__SECRET 中设置"
+        );
+        ensure!(self.jwt.secret.len() >= 16, "jwt.secret 长度不能少于 16 个字符");
+        ensure!(self.jwt.expires_in > 0, "jwt.expires_in 必须大于 0");
+        ensure!(self.scheduler.interval > 0, "scheduler.interval 必须大于 0");
+        ensure!(
+            self.maintenance.service_hours.is_finite() && self.maintenance.service_hours >= 0.0,
+            "maintenance.service_hours 不能为负数"
+        );
+
+        Ok(())
+    }
+}
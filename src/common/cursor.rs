@@ -0,0 +1,181 @@
+use base64::Engine;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use chrono::{ DateTime, NaiveDateTime };
+use sea_orm::{
+    ColumnTrait,
+    Condition,
+    ConnectionTrait,
+    EntityTrait,
+    QueryFilter,
+    QueryOrder,
+    QuerySelect,
+    Select,
+    Value,
+};
+
+use super::page::{ CursorPage, CursorParams };
+use super::result::{ ApiError, ApiResult };
+use crate::t;
+
+// 游标中的主键，日志和遥测为自增整数，事件为字符串
+#[derive(Debug, Clone)]
+pub enum CursorId {
+    Int(i64),
+    Text(String),
+}
+
+impl From<CursorId> for Value {
+    fn from(id: CursorId) -> Self {
+        match id {
+            CursorId::Int(id) => Value::from(id),
+            CursorId::Text(id) => Value::from(id),
+        }
+    }
+}
+
+// 游标记录上一页最后一条的时间和主键，对客户端不透明；只按主键遍历的实体不记录时间
+#[derive(Debug, Clone)]
+pub struct Cursor {
+    pub time: Option<NaiveDateTime>,
+    pub id: CursorId,
+}
+
+impl Cursor {
+    pub fn encode(&self) -> String {
+        let id = match &self.id {
+            CursorId::Int(id) => format!("i{}", id),
+            CursorId::Text(id) => format!("s{}", id),
+        };
+        let time = self.time
+            .map(|time| time.and_utc().timestamp_micros().to_string())
+            .unwrap_or_default();
+        URL_SAFE_NO_PAD.encode(format!("{}|{}", time, id))
+    }
+
+    pub fn decode(token: &str) -> Option<Self> {
+        let raw = String::from_utf8(URL_SAFE_NO_PAD.decode(token).ok()?).ok()?;
+        let (time, id) = raw.split_once('|')?;
+
+        let time = match time {
+            "" => None,
+            time => Some(DateTime::from_timestamp_micros(time.parse().ok()?)?.naive_utc()),
+        };
+        let id = match id.split_at_checked(1)? {
+            ("i", id) => CursorId::Int(id.parse().ok()?),
+            ("s", id) => CursorId::Text(id.to_string()),
+            _ => {
+                return None;
+            }
+        };
+
+        Some(Cursor { time, id })
+    }
+}
+
+// 支持游标分页的实体，按 (时间, 主键) 倒序遍历；没有时间列时只按主键倒序遍历
+pub trait Keyset: EntityTrait {
+    fn time_column() -> Option<Self::Column>;
+
+    fn id_column() -> Self::Column;
+
+    fn cursor_of(model: &Self::Model) -> Cursor;
+}
+
+// 按游标取一页数据，多取一条用于判断是否还有下一页
+pub async fn fetch<E, C>(
+    conn: &C,
+    select: Select<E>,
+    params: &CursorParams
+) -> ApiResult<CursorPage<E::Model>>
+    where E: Keyset, C: ConnectionTrait
+{
+    let limit = params.limit();
+    let (time_column, id_column) = (E::time_column(), E::id_column());
+
+    let mut select = select;
+    if let Some(time_column) = time_column {
+        select = select.order_by_desc(time_column);
+    }
+    select = select.order_by_desc(id_column);
+
+    if let Some(token) = params.cursor.as_deref() {
+        let invalid = || ApiError::BadRequest(t!("cursor.invalid"));
+        let cursor = Cursor::decode(token).ok_or_else(invalid)?;
+
+        let condition = match time_column {
+            Some(time_column) => {
+                let time = cursor.time.ok_or_else(invalid)?;
+                Condition::any()
+                    .add(time_column.lt(time))
+                    .add(
+                        Condition::all()
+                            .add(time_column.eq(time))
+                            .add(id_column.lt(Value::from(cursor.id)))
+                    )
+            }
+            None => Condition::all().add(id_column.lt(Value::from(cursor.id))),
+        };
+        select = select.filter(condition);
+    }
+
+    let mut items = select
+        .limit(limit + 1)
+        .all(conn).await?;
+
+    let next_cursor = if (items.len() as u64) > limit {
+        items.truncate(limit as usize);
+        items.last().map(|model| E::cursor_of(model).encode())
+    } else {
+        None
+    };
+
+    Ok(CursorPage { limit, next_cursor, items })
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+
+    use super::*;
+
+    fn time() -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2025, 7, 1).unwrap().and_hms_micro_opt(8, 30, 15, 123_456).unwrap()
+    }
+
+    #[test]
+    fn round_trips_int_id() {
+        let encoded = Cursor { time: Some(time()), id: CursorId::Int(42) }.encode();
+        let cursor = Cursor::decode(&encoded).unwrap();
+        assert_eq!(cursor.time, Some(time()));
+        assert!(matches!(cursor.id, CursorId::Int(42)));
+    }
+
+    #[test]
+    fn round_trips_text_id() {
+        let id = CursorId::Text("d1a|b2".to_string());
+        let encoded = Cursor { time: Some(time()), id }.encode();
+        let cursor = Cursor::decode(&encoded).unwrap();
+        assert_eq!(cursor.time, Some(time()));
+        assert!(matches!(cursor.id, CursorId::Text(ref id) if id == "d1a|b2"));
+    }
+
+    #[test]
+    fn round_trips_without_time() {
+        let cursor = Cursor::decode(&Cursor { time: None, id: CursorId::Int(7) }.encode()).unwrap();
+        assert_eq!(cursor.time, None);
+        assert!(matches!(cursor.id, CursorId::Int(7)));
+    }
+
+    #[test]
+    fn rejects_malformed_tokens() {
+        let encode = |raw: &str| URL_SAFE_NO_PAD.encode(raw);
+
+        assert!(Cursor::decode("not base64!").is_none());
+        assert!(Cursor::decode(&URL_SAFE_NO_PAD.encode([0xff, 0xfe])).is_none());
+        assert!(Cursor::decode(&encode("1751358615000000")).is_none());
+        assert!(Cursor::decode(&encode("abc|i1")).is_none());
+        assert!(Cursor::decode(&encode("1751358615000000|x1")).is_none());
+        assert!(Cursor::decode(&encode("1751358615000000|iabc")).is_none());
+        assert!(Cursor::decode(&encode("1751358615000000|")).is_none());
+    }
+}
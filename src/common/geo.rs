@@ -0,0 +1,301 @@
+use rust_decimal::prelude::{ FromPrimitive, ToPrimitive };
+use rust_decimal::{ Decimal, RoundingStrategy };
+use sea_orm::{ ColumnTrait, Condition };
+
+// 地球平均半径（米）
+pub const EARTH_RADIUS: f64 = 6_371_008.8;
+
+pub fn to_f64(value: &Decimal) -> f64 {
+    value.to_f64().unwrap_or_default()
+}
+
+// 按列精度（6 位小数）取整，下界向下、上界向上，避免边界上的数据被排除
+fn to_decimal(value: f64, strategy: RoundingStrategy) -> Decimal {
+    Decimal::from_f64(value).unwrap_or_default().round_dp_with_strategy(6, strategy)
+}
+
+// 两点间的大圆距离（米）
+pub fn haversine(lat1: f64, lng1: f64, lat2: f64, lng2: f64) -> f64 {
+    let (phi1, phi2) = (lat1.to_radians(), lat2.to_radians());
+    let d_phi = (lat2 - lat1).to_radians();
+    let d_lambda = (lng2 - lng1).to_radians();
+
+    let a = (d_phi / 2.0).sin().powi(2) + phi1.cos() * phi2.cos() * (d_lambda / 2.0).sin().powi(2);
+    2.0 * EARTH_RADIUS * a.sqrt().min(1.0).asin()
+}
+
+// 经纬度矩形，min_lng > max_lng 表示跨越 180° 经线
+#[derive(Debug, Clone, Copy)]
+pub struct BoundingBox {
+    pub min_lat: f64,
+    pub max_lat: f64,
+    pub min_lng: f64,
+    pub max_lng: f64,
+}
+
+impl BoundingBox {
+    // 包含以 (lat, lng) 为中心、distance 米为半径的圆的最小矩形
+    pub fn around(lat: f64, lng: f64, distance: f64) -> Self {
+        let d_lat = (distance / EARTH_RADIUS).to_degrees();
+        let min_lat = (lat - d_lat).max(-90.0);
+        let max_lat = (lat + d_lat).min(90.0);
+
+        // 覆盖到极点时经度不再受限
+        let cos_lat = lat.to_radians().cos();
+        if min_lat <= -90.0 || max_lat >= 90.0 || cos_lat <= f64::EPSILON {
+            return BoundingBox { min_lat, max_lat, min_lng: -180.0, max_lng: 180.0 };
+        }
+
+        let d_lng = d_lat / cos_lat;
+        if d_lng >= 180.0 {
+            return BoundingBox { min_lat, max_lat, min_lng: -180.0, max_lng: 180.0 };
+        }
+
+        let wrap = |value: f64| {
+            if value < -180.0 {
+                value + 360.0
+            } else if value > 180.0 {
+                value - 360.0
+            } else {
+                value
+            }
+        };
+        BoundingBox { min_lat, max_lat, min_lng: wrap(lng - d_lng), max_lng: wrap(lng + d_lng) }
+    }
+
+    // 只用范围比较，MySQL 和 SQLite 均可使用 (lat, lng) 索引
+    pub fn condition<C: ColumnTrait>(&self, lat: C, lng: C) -> Condition {
+        let floor = |value| to_decimal(value, RoundingStrategy::ToNegativeInfinity);
+        let ceil = |value| to_decimal(value, RoundingStrategy::ToPositiveInfinity);
+
+        let lat_range = lat.between(floor(self.min_lat), ceil(self.max_lat));
+        let (min_lng, max_lng) = (floor(self.min_lng), ceil(self.max_lng));
+
+        let lng_range = if self.min_lng <= self.max_lng {
+            Condition::all().add(lng.between(min_lng, max_lng))
+        } else {
+            Condition::any().add(lng.gte(min_lng)).add(lng.lte(max_lng))
+        };
+
+        Condition::all().add(lat_range).add(lng_range)
+    }
+}
+
+// 经纬度坐标（度）
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Point {
+    pub lat: f64,
+    pub lng: f64,
+}
+
+impl Point {
+    pub fn new(lat: &Decimal, lng: &Decimal) -> Self {
+        Point { lat: to_f64(lat), lng: to_f64(lng) }
+    }
+
+    pub fn distance(&self, other: &Point) -> f64 {
+        haversine(self.lat, self.lng, other.lat, other.lng)
+    }
+
+    // 以 origin 为原点的局部平面坐标（米），适用于几十公里内的近似计算
+    fn project(&self, origin: &Point) -> (f64, f64) {
+        let scale = EARTH_RADIUS * std::f64::consts::PI / 180.0;
+        let x = (self.lng - origin.lng) * origin.lat.to_radians().cos() * scale;
+        let y = (self.lat - origin.lat) * scale;
+        (x, y)
+    }
+}
+
+// 平面上点 p 到线段 ab 的最短距离
+fn segment_distance((px, py): (f64, f64), (ax, ay): (f64, f64), (bx, by): (f64, f64)) -> f64 {
+    let (dx, dy) = (bx - ax, by - ay);
+    let length = dx * dx + dy * dy;
+    let t = if length == 0.0 { 0.0 } else { (((px - ax) * dx + (py - ay) * dy) / length).clamp(0.0, 1.0) };
+    (px - (ax + t * dx)).hypot(py - (ay + t * dy))
+}
+
+// 叉积符号，判断 c 在有向线段 ab 的哪一侧
+fn orientation(a: &Point, b: &Point, c: &Point) -> f64 {
+    (b.lng - a.lng) * (c.lat - a.lat) - (b.lat - a.lat) * (c.lng - a.lng)
+}
+
+fn on_segment(a: &Point, b: &Point, c: &Point) -> bool {
+    c.lng >= a.lng.min(b.lng) &&
+        c.lng <= a.lng.max(b.lng) &&
+        c.lat >= a.lat.min(b.lat) &&
+        c.lat <= a.lat.max(b.lat)
+}
+
+// 线段相交（含端点接触与共线重叠），经纬度的线性缩放不改变相交关系
+fn segments_intersect(a1: &Point, a2: &Point, b1: &Point, b2: &Point) -> bool {
+    let (d1, d2) = (orientation(b1, b2, a1), orientation(b1, b2, a2));
+    let (d3, d4) = (orientation(a1, a2, b1), orientation(a1, a2, b2));
+
+    if ((d1 > 0.0 && d2 < 0.0) || (d1 < 0.0 && d2 > 0.0)) && ((d3 > 0.0 && d4 < 0.0) || (d3 < 0.0 && d4 > 0.0)) {
+        return true;
+    }
+    (d1 == 0.0 && on_segment(b1, b2, a1)) ||
+        (d2 == 0.0 && on_segment(b1, b2, a2)) ||
+        (d3 == 0.0 && on_segment(a1, a2, b1)) ||
+        (d4 == 0.0 && on_segment(a1, a2, b2))
+}
+
+// 圆形（半径单位为米）或多边形区域，多边形不跨越 180° 经线
+#[derive(Debug, Clone)]
+pub enum Shape {
+    Circle { center: Point, radius: f64 },
+    Polygon(Vec<Point>),
+}
+
+impl Shape {
+    // 射线法判断点是否在多边形内
+    pub fn contains(&self, point: &Point) -> bool {
+        match self {
+            Shape::Circle { center, radius } => center.distance(point) <= *radius,
+            Shape::Polygon(vertices) => {
+                let mut inside = false;
+                let mut j = vertices.len().wrapping_sub(1);
+                for (i, a) in vertices.iter().enumerate() {
+                    let b = &vertices[j];
+                    if
+                        (a.lat > point.lat) != (b.lat > point.lat) &&
+                        point.lng < ((b.lng - a.lng) * (point.lat - a.lat)) / (b.lat - a.lat) + a.lng
+                    {
+                        inside = !inside;
+                    }
+                    j = i;
+                }
+                inside
+            }
+        }
+    }
+
+    // 线段 ab 是否经过区域
+    pub fn intersects(&self, a: &Point, b: &Point) -> bool {
+        if self.contains(a) || self.contains(b) {
+            return true;
+        }
+        match self {
+            Shape::Circle { center, radius } => {
+                segment_distance((0.0, 0.0), a.project(center), b.project(center)) <= *radius
+            }
+            Shape::Polygon(vertices) =>
+                vertices
+                    .iter()
+                    .zip(vertices.iter().cycle().skip(1))
+                    .any(|(v1, v2)| segments_intersect(a, b, v1, v2)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn point(lat: f64, lng: f64) -> Point {
+        Point { lat, lng }
+    }
+
+    fn square() -> Shape {
+        Shape::Polygon(vec![
+            point(30.0, 120.0),
+            point(30.0, 120.1),
+            point(30.1, 120.1),
+            point(30.1, 120.0),
+        ])
+    }
+
+    #[test]
+    fn haversine_matches_known_distances() {
+        assert_eq!(haversine(30.0, 120.0, 30.0, 120.0), 0.0);
+
+        // 纬度差 1° 约 111.195 km
+        let one_degree = haversine(0.0, 0.0, 1.0, 0.0);
+        assert!((one_degree - 111_195.0).abs() < 1.0, "{}", one_degree);
+
+        // 北京到上海约 1067 km，且与方向无关
+        let there = haversine(39.9042, 116.4074, 31.2304, 121.4737);
+        let back = haversine(31.2304, 121.4737, 39.9042, 116.4074);
+        assert!((there - 1_067_000.0).abs() < 5_000.0, "{}", there);
+        assert!((there - back).abs() < 1e-6);
+
+        // 跨越 180° 经线时取短的一侧
+        assert!(haversine(0.0, 179.9, 0.0, -179.9) < 23_000.0);
+    }
+
+    #[test]
+    fn bounding_box_contains_the_circle() {
+        let bbox = BoundingBox::around(30.0, 120.0, 10_000.0);
+
+        assert!(bbox.min_lat < 30.0 && bbox.max_lat > 30.0);
+        assert!(bbox.min_lng < 120.0 && bbox.max_lng > 120.0);
+        // 纬度越高经度跨度越大
+        assert!(bbox.max_lng - bbox.min_lng > bbox.max_lat - bbox.min_lat);
+        // 矩形的边贴着圆：正北的边正好是半径，东侧沿纬线的距离略短于大圆上的半径
+        let north = haversine(30.0, 120.0, bbox.max_lat, 120.0);
+        let east = haversine(30.0, 120.0, 30.0, bbox.max_lng);
+        assert!((north - 10_000.0).abs() < 1.0, "{}", north);
+        assert!((east - 10_000.0).abs() < 10.0, "{}", east);
+    }
+
+    #[test]
+    fn bounding_box_wraps_across_the_antimeridian() {
+        let bbox = BoundingBox::around(0.0, 179.95, 20_000.0);
+
+        assert!(bbox.min_lng > bbox.max_lng);
+        assert!(bbox.min_lng > 179.0 && bbox.min_lng < 179.95);
+        assert!(bbox.max_lng < -179.0 && bbox.max_lng > -180.0);
+
+        let bbox = BoundingBox::around(0.0, -179.95, 20_000.0);
+        assert!(bbox.min_lng > 179.0 && bbox.max_lng < -179.0);
+    }
+
+    #[test]
+    fn bounding_box_clamps_at_the_poles() {
+        let bbox = BoundingBox::around(89.95, 10.0, 20_000.0);
+        assert_eq!(bbox.max_lat, 90.0);
+        assert_eq!((bbox.min_lng, bbox.max_lng), (-180.0, 180.0));
+
+        let bbox = BoundingBox::around(-89.95, 10.0, 20_000.0);
+        assert_eq!(bbox.min_lat, -90.0);
+        assert_eq!((bbox.min_lng, bbox.max_lng), (-180.0, 180.0));
+    }
+
+    #[test]
+    fn polygon_contains_points() {
+        let square = square();
+        assert!(square.contains(&point(30.05, 120.05)));
+        assert!(!square.contains(&point(30.2, 120.05)));
+        assert!(!square.contains(&point(30.05, 119.9)));
+    }
+
+    #[test]
+    fn polygon_intersects_segments() {
+        let square = square();
+
+        // 两端都在外面但穿过区域
+        assert!(square.intersects(&point(30.05, 119.9), &point(30.05, 120.2)));
+        // 一端在区域内
+        assert!(square.intersects(&point(30.05, 120.05), &point(31.0, 121.0)));
+        // 端点落在边上
+        assert!(square.intersects(&point(29.9, 120.05), &point(30.0, 120.05)));
+        // 从旁边经过
+        assert!(!square.intersects(&point(30.2, 119.9), &point(30.2, 120.2)));
+        assert!(!square.intersects(&point(29.0, 119.0), &point(29.5, 119.5)));
+    }
+
+    #[test]
+    fn circle_intersects_segments() {
+        let circle = Shape::Circle {
+            center: point(30.0, 120.0),
+            radius: 1_000.0,
+        };
+
+        assert!(circle.contains(&point(30.005, 120.0)));
+        assert!(!circle.contains(&point(30.02, 120.0)));
+        // 两端都在圆外，但线段经过圆心附近
+        assert!(circle.intersects(&point(30.0, 119.95), &point(30.0, 120.05)));
+        // 线段离圆心约 2.2 km
+        assert!(!circle.intersects(&point(30.02, 119.95), &point(30.02, 120.05)));
+    }
+}
@@ -0,0 +1,127 @@
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::sync::LazyLock;
+
+use axum::extract::Request;
+use axum::http::header::{ ACCEPT_LANGUAGE, CONTENT_LANGUAGE };
+use axum::http::{ HeaderMap, HeaderValue };
+use axum::middleware::Next;
+use axum::response::Response;
+
+// 按消息 ID 查找文案，占位符写作 {name}
+#[macro_export]
+macro_rules! t {
+    ($key:expr) => {
+        $crate::common::i18n::translate($key, &[])
+    };
+    ($key:expr, $($name:ident = $value:expr),+ $(,)?) => {
+        $crate::common::i18n::translate(
+            $key,
+            &[$((stringify!($name), &$value as &dyn std::fmt::Display)),+]
+        )
+    };
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Locale {
+    #[default]
+    ZhCn,
+    En,
+}
+
+impl Locale {
+    pub fn tag(self) -> &'static str {
+        match self {
+            Locale::ZhCn => "zh-CN",
+            Locale::En => "en",
+        }
+    }
+
+    fn from_tag(tag: &str) -> Option<Self> {
+        let primary = tag.split(['-', '_']).next().unwrap_or_default().to_ascii_lowercase();
+        match primary.as_str() {
+            "zh" => Some(Locale::ZhCn),
+            "en" => Some(Locale::En),
+            _ => None,
+        }
+    }
+
+    // 按 q 值从高到低选取第一个支持的语言，例如 en-US,en;q=0.9,zh;q=0.8
+    pub fn from_headers(headers: &HeaderMap) -> Self {
+        let Some(value) = headers.get(ACCEPT_LANGUAGE).and_then(|value| value.to_str().ok()) else {
+            return Locale::default();
+        };
+
+        let mut candidates: Vec<(f32, &str)> = value
+            .split(',')
+            .filter_map(|item| {
+                let mut parts = item.split(';');
+                let tag = parts.next()?.trim();
+                let quality = parts
+                    .find_map(|param| param.trim().strip_prefix("q="))
+                    .map_or(Some(1.0), |q| q.trim().parse::<f32>().ok())?;
+                Some((quality, tag))
+            })
+            .filter(|(quality, _)| *quality > 0.0)
+            .collect();
+        candidates.sort_by(|a, b| b.0.total_cmp(&a.0));
+
+        candidates
+            .into_iter()
+            .find_map(|(_, tag)| Locale::from_tag(tag))
+            .unwrap_or_default()
+    }
+}
+
+type Catalog = HashMap<String, String>;
+
+fn load(source: &str) -> Catalog {
+    serde_json::from_str(source).expect("语言包格式错误")
+}
+
+static ZH_CN: LazyLock<Catalog> = LazyLock::new(|| load(include_str!("../../locales/zh-CN.json")));
+static EN: LazyLock<Catalog> = LazyLock::new(|| load(include_str!("../../locales/en.json")));
+
+fn catalog(locale: Locale) -> &'static Catalog {
+    match locale {
+        Locale::ZhCn => &ZH_CN,
+        Locale::En => &EN,
+    }
+}
+
+tokio::task_local! {
+    static LOCALE: Locale;
+}
+
+// 当前请求的语言，不在请求上下文中时使用默认语言
+pub fn current() -> Locale {
+    LOCALE.try_with(|locale| *locale).unwrap_or_default()
+}
+
+// 在指定语言下运行，用于脱离请求上下文继续执行的任务（如 WebSocket 连接）
+pub async fn with_locale<F: Future>(locale: Locale, future: F) -> F::Output {
+    LOCALE.scope(locale, future).await
+}
+
+// 缺少译文时回退到中文，仍找不到则原样返回消息 ID
+pub fn translate(key: &str, args: &[(&str, &dyn Display)]) -> String {
+    let template = catalog(current())
+        .get(key)
+        .or_else(|| ZH_CN.get(key))
+        .map_or(key, String::as_str);
+
+    let mut message = template.to_string();
+    for (name, value) in args {
+        message = message.replace(&format!("{{{}}}", name), &value.to_string());
+    }
+    message
+}
+
+// 根据 Accept-Language 确定本次请求的语言，并在响应中标明
+pub async fn localize(request: Request, next: Next) -> Response {
+    let locale = Locale::from_headers(request.headers());
+
+    let mut response = LOCALE.scope(locale, next.run(request)).await;
+    response.headers_mut().insert(CONTENT_LANGUAGE, HeaderValue::from_static(locale.tag()));
+    response
+}
@@ -0,0 +1,54 @@
+use std::time::{ Duration, SystemTime, UNIX_EPOCH };
+
+use jsonwebtoken::{ DecodingKey, EncodingKey, Header, Validation };
+use serde::{ Deserialize, Serialize };
+
+use super::config::JwtConfig;
+use crate::entity::sea_orm_active_enums::Role;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Claims {
+    pub user_id: String,
+    pub role: Role,
+    pub iat: u64,
+    pub exp: u64,
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+impl Claims {
+    // 令牌剩余有效时间，已过期时为 0
+    pub fn remaining(&self) -> Duration {
+        Duration::from_secs(self.exp.saturating_sub(now()))
+    }
+}
+
+pub fn encode_token(
+    config: &JwtConfig,
+    user_id: String,
+    role: Role
+) -> jsonwebtoken::errors::Result<String> {
+    let iat = now();
+    let claims = Claims { user_id, role, iat, exp: iat + config.expires_in };
+
+    jsonwebtoken::encode(
+        &Header::default(),
+        &claims,
+        &EncodingKey::from_secret(config.secret.as_bytes())
+    )
+}
+
+pub fn decode_token(config: &JwtConfig, token: &str) -> jsonwebtoken::errors::Result<Claims> {
+    jsonwebtoken
+        ::decode::<Claims>(
+            token,
+            &DecodingKey::from_secret(config.secret.as_bytes()),
+            &Validation::default()
+        )
+        .map(|data| data.claims)
+}
@@ -1,6 +1,9 @@
 use std::fmt::Display;
 use std::str::FromStr;
 use serde::{ Deserialize, Serialize };
+use utoipa::{ IntoParams, ToSchema };
+
+use crate::t;
 
 // 默认页码
 const DEFAULT_PAGE: u64 = 1;
@@ -8,6 +11,10 @@ const DEFAULT_PAGE: u64 = 1;
 // 默认数据条数
 const DEFAULT_SIZE: u64 = 5;
 
+// 游标分页默认和最大条数
+const DEFAULT_LIMIT: u64 = 20;
+const MAX_LIMIT: u64 = 100;
+
 #[derive(Deserialize)]
 #[serde(untagged)]
 enum StringOrNumber<T> {
@@ -15,7 +22,7 @@ enum StringOrNumber<T> {
     Number(T),
 }
 
-fn deserialize_number<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+pub fn deserialize_number<'de, T, D>(deserializer: D) -> Result<T, D::Error>
     where T: FromStr + Deserialize<'de>, T::Err: Display, D: serde::Deserializer<'de>
 {
     match StringOrNumber::deserialize(deserializer)? {
@@ -24,6 +31,26 @@ fn deserialize_number<'de, T, D>(deserializer: D) -> Result<T, D::Error>
     }
 }
 
+pub fn deserialize_optional_number<'de, T, D>(deserializer: D) -> Result<Option<T>, D::Error>
+    where T: FromStr + Deserialize<'de>, T::Err: Display, D: serde::Deserializer<'de>
+{
+    match Option::<StringOrNumber<T>>::deserialize(deserializer)? {
+        Some(StringOrNumber::String(s)) => s.parse().map(Some).map_err(serde::de::Error::custom),
+        Some(StringOrNumber::Number(n)) => Ok(Some(n)),
+        None => Ok(None),
+    }
+}
+
+// 页码和条数从 1 开始，避免 page - 1 下溢
+fn deserialize_positive<'de, D>(deserializer: D) -> Result<u64, D::Error>
+    where D: serde::Deserializer<'de>
+{
+    match deserialize_number::<u64, D>(deserializer)? {
+        0 => Err(serde::de::Error::custom(t!("validation.page_positive"))),
+        n => Ok(n),
+    }
+}
+
 fn default_page() -> u64 {
     DEFAULT_PAGE
 }
@@ -32,16 +59,52 @@ fn default_size() -> u64 {
     DEFAULT_SIZE
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct PaginationParams {
-    #[serde(default = "default_page", deserialize_with = "deserialize_number")]
+    /// 页码，从 1 开始
+    #[param(minimum = 1, default = 1)]
+    #[serde(default = "default_page", deserialize_with = "deserialize_positive")]
     pub page: u64,
 
-    #[serde(default = "default_size", deserialize_with = "deserialize_number")]
+    /// 每页条数
+    #[param(minimum = 1, default = 5)]
+    #[serde(default = "default_size", deserialize_with = "deserialize_positive")]
     pub size: u64,
 }
 
-#[derive(Debug, Serialize)]
+impl PaginationParams {
+    // 传给 fetch_page 的页下标（从 0 开始）
+    pub fn index(&self) -> u64 {
+        self.page.saturating_sub(1)
+    }
+}
+
+// 游标分页参数：?cursor=...&limit=...，首次请求不带 cursor
+#[derive(Debug, Clone, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct CursorParams {
+    /// 上一页返回的 next_cursor
+    pub cursor: Option<String>,
+
+    /// 每页条数，带上该参数即使用游标分页
+    #[param(minimum = 1, maximum = 100, default = 20)]
+    #[serde(default, deserialize_with = "deserialize_optional_number")]
+    pub limit: Option<u64>,
+}
+
+impl CursorParams {
+    // 带了 cursor 或 limit 即使用游标分页
+    pub fn is_active(&self) -> bool {
+        self.cursor.is_some() || self.limit.is_some()
+    }
+
+    pub fn limit(&self) -> u64 {
+        self.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT)
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
 pub struct Page<T> {
     pub page: u64,
     pub size: u64,
@@ -58,3 +121,18 @@ impl<T> Page<T> {
         Self::new(pagination.page, pagination.size, total, items)
     }
 }
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct CursorPage<T> {
+    pub limit: u64,
+    pub next_cursor: Option<String>,
+    pub items: Vec<T>,
+}
+
+// 支持两种分页方式的列表结果
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(untagged)]
+pub enum Listing<T> {
+    Page(Page<T>),
+    Cursor(CursorPage<T>),
+}
@@ -0,0 +1,25 @@
+// bcrypt 哈希值的固定长度
+const BCRYPT_HASH_LEN: usize = 60;
+
+// 用户不存在时用于比对的哈希值，代价与 DEFAULT_COST 相同，使两种失败的耗时一致
+pub const DUMMY_HASH: &str = "$2b$12$nA6uQghYyH3UqpjLYVY4ceZLpRnS7WZvYq.ijK3pzRpWmdNOzjS5a";
+
+// bcrypt 计算耗时较长，放到阻塞线程池执行，避免占住异步运行时的工作线程
+pub async fn hash(password: &str) -> anyhow::Result<String> {
+    let password = password.to_owned();
+    let hashed = tokio::task::spawn_blocking(move || bcrypt::hash(password, bcrypt::DEFAULT_COST)).await??;
+    Ok(hashed)
+}
+
+pub async fn verify(password: &str, hashed: &str) -> bool {
+    let (password, hashed) = (password.to_owned(), hashed.to_owned());
+    tokio::task::spawn_blocking(move || bcrypt::verify(password, &hashed).unwrap_or(false))
+        .await
+        .unwrap_or(false)
+}
+
+// 判断存储的密码是否已经是 bcrypt 哈希值
+pub fn is_hashed(stored: &str) -> bool {
+    stored.len() == BCRYPT_HASH_LEN &&
+        (stored.starts_with("$2a$") || stored.starts_with("$2b$") || stored.starts_with("$2y$"))
+}
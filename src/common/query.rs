@@ -0,0 +1,368 @@
+use std::collections::HashMap;
+use std::marker::PhantomData;
+use std::str::FromStr;
+
+use chrono::{ NaiveDate, NaiveDateTime };
+use sea_orm::sea_query::IntoCondition;
+use sea_orm::{
+    ActiveEnum,
+    ColumnTrait,
+    Condition,
+    EntityTrait,
+    Iterable,
+    Order,
+    QueryFilter,
+    QueryOrder,
+    Select,
+    Value,
+};
+use sea_orm::prelude::Decimal;
+use serde::Deserialize;
+use utoipa::IntoParams;
+use utoipa::openapi::path::{ Parameter, ParameterBuilder, ParameterIn };
+use utoipa::openapi::{ ObjectBuilder, Required, Type };
+
+use super::result::{ ApiError, ApiResult };
+use crate::t;
+
+// 字段类型，决定查询参数如何解析以及支持哪些比较方式
+#[derive(Clone, Copy)]
+pub enum FieldKind {
+    Text,
+    Enum(fn(&str) -> Option<Value>),
+    Integer,
+    Float,
+    Decimal,
+    DateTime,
+}
+
+// 允许排序和过滤的字段
+pub struct QueryField<C> {
+    pub name: &'static str,
+    pub column: C,
+    pub kind: FieldKind,
+}
+
+impl<C> QueryField<C> {
+    pub fn new(name: &'static str, column: C, kind: FieldKind) -> Self {
+        QueryField { name, column, kind }
+    }
+}
+
+// 按数据库中的取值（不区分大小写）解析枚举
+pub fn parse_variant<T>(value: &str) -> Option<T> where T: ActiveEnum<Value = String> + Iterable {
+    T::iter().find(|variant| variant.to_value().eq_ignore_ascii_case(value))
+}
+
+// 同 parse_variant，返回用于查询条件的数据库取值
+pub fn parse_enum<T>(value: &str) -> Option<Value> where T: ActiveEnum<Value = String> + Iterable {
+    parse_variant::<T>(value).map(|variant| Value::from(variant.to_value()))
+}
+
+// 列表接口可查询的实体，声明字段白名单和关键字搜索的列
+pub trait Queryable: EntityTrait {
+    fn fields() -> Vec<QueryField<Self::Column>>;
+
+    fn search_columns() -> Vec<Self::Column> {
+        Vec::new()
+    }
+
+    // 未指定 sort 时的排序
+    fn default_sort() -> Vec<(Self::Column, Order)>;
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Op {
+    Eq,
+    Ne,
+    Lt,
+    Lte,
+    Gt,
+    Gte,
+}
+
+// 参数名后缀与比较方式，长后缀在前以免 _lte 被误识别为 _lt
+const SUFFIXES: [(&str, Op); 7] = [
+    ("_lte", Op::Lte),
+    ("_gte", Op::Gte),
+    ("_lt", Op::Lt),
+    ("_gt", Op::Gt),
+    ("_ne", Op::Ne),
+    ("_after", Op::Gte),
+    ("_before", Op::Lte),
+];
+
+// 通用列表查询参数：sort=name,-battery&q=关键字&status=working&battery_lt=20&created_after=2025-01-01
+#[derive(Debug, Default, Deserialize)]
+pub struct ListQuery {
+    pub sort: Option<String>,
+    pub q: Option<String>,
+    #[serde(flatten)]
+    pub filters: HashMap<String, String>,
+}
+
+fn bad_request(message: String) -> ApiError {
+    ApiError::BadRequest(message)
+}
+
+fn parse_datetime(value: &str) -> Option<NaiveDateTime> {
+    NaiveDateTime::parse_from_str(value, "%Y-%m-%dT%H:%M:%S")
+        .or_else(|_| NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S"))
+        .ok()
+        .or_else(|| {
+            NaiveDate::parse_from_str(value, "%Y-%m-%d")
+                .ok()
+                .and_then(|date| date.and_hms_opt(0, 0, 0))
+        })
+}
+
+fn parse_number<T: FromStr + Into<Value>>(value: &str) -> Option<Value> {
+    value.parse::<T>().ok().map(Into::into)
+}
+
+fn parse_value(kind: FieldKind, value: &str) -> Option<Value> {
+    match kind {
+        FieldKind::Text => Some(Value::from(value)),
+        FieldKind::Enum(parse) => parse(value),
+        FieldKind::Integer => parse_number::<i64>(value),
+        FieldKind::Float => parse_number::<f64>(value),
+        FieldKind::Decimal => parse_number::<Decimal>(value),
+        FieldKind::DateTime => parse_datetime(value).map(Value::from),
+    }
+}
+
+impl ListQuery {
+    pub fn apply<E: Queryable>(&self, select: Select<E>) -> ApiResult<Select<E>> {
+        let select = self.filter(select)?;
+        self.sort(select)
+    }
+
+    // 游标分页固定按时间和主键排序，只应用过滤和搜索条件
+    pub fn apply_for_cursor<E: Queryable>(&self, select: Select<E>) -> ApiResult<Select<E>> {
+        if self.sort.is_some() {
+            return Err(bad_request(t!("query.cursor_sort")));
+        }
+        self.filter(select)
+    }
+
+    // 结果在应用层排序（如按距离）时使用，只应用过滤和搜索条件
+    pub fn apply_filters<E: Queryable>(&self, select: Select<E>) -> ApiResult<Select<E>> {
+        if self.sort.is_some() {
+            return Err(bad_request(t!("query.sort_unsupported")));
+        }
+        self.filter(select)
+    }
+
+    fn filter<E: Queryable>(&self, mut select: Select<E>) -> ApiResult<Select<E>> {
+        let fields = E::fields();
+
+        for (key, value) in &self.filters {
+            let (field, op) = Self::resolve(&fields, key).ok_or_else(||
+                bad_request(t!("query.unsupported_param", key = key))
+            )?;
+            select = select.filter(Self::condition(field, op, key, value)?);
+        }
+
+        if let Some(q) = self.q.as_deref().map(str::trim).filter(|q| !q.is_empty()) {
+            let columns = E::search_columns();
+            if columns.is_empty() {
+                return Err(bad_request(t!("query.search_unsupported")));
+            }
+
+            let condition = columns
+                .into_iter()
+                .fold(Condition::any(), |condition, column| condition.add(column.contains(q)));
+            select = select.filter(condition);
+        }
+
+        Ok(select)
+    }
+
+    fn sort<E: Queryable>(&self, mut select: Select<E>) -> ApiResult<Select<E>> {
+        let fields = E::fields();
+
+        match self.sort.as_deref().filter(|sort| !sort.trim().is_empty()) {
+            Some(sort) => {
+                for item in sort.split(',').map(str::trim).filter(|item| !item.is_empty()) {
+                    let (name, order) = match item.strip_prefix('-') {
+                        Some(name) => (name, Order::Desc),
+                        None => (item.trim_start_matches('+'), Order::Asc),
+                    };
+                    let field = fields
+                        .iter()
+                        .find(|field| field.name == name)
+                        .ok_or_else(|| bad_request(t!("query.unsupported_sort", name = name)))?;
+                    select = select.order_by(field.column, order);
+                }
+            }
+            None => {
+                for (column, order) in E::default_sort() {
+                    select = select.order_by(column, order);
+                }
+            }
+        }
+
+        Ok(select)
+    }
+
+    // 解析参数名对应的字段和比较方式；时间字段的 _after/_before 可省略 _at，如 created_after
+    fn resolve<'a, C>(fields: &'a [QueryField<C>], key: &str) -> Option<(&'a QueryField<C>, Op)> {
+        if let Some(field) = fields.iter().find(|field| field.name == key) {
+            return Some((field, Op::Eq));
+        }
+
+        SUFFIXES.iter().find_map(|(suffix, op)| {
+            let base = key.strip_suffix(suffix)?;
+            let field = fields.iter().find(|field| {
+                field.name == base ||
+                    (matches!(field.kind, FieldKind::DateTime) &&
+                        field.name.strip_suffix("_at") == Some(base))
+            })?;
+
+            let comparable = !matches!(field.kind, FieldKind::Text | FieldKind::Enum(_));
+            let is_time_suffix = *suffix == "_after" || *suffix == "_before";
+            let allowed = match op {
+                Op::Ne => true,
+                _ if is_time_suffix => matches!(field.kind, FieldKind::DateTime),
+                _ => comparable,
+            };
+
+            allowed.then_some((field, *op))
+        })
+    }
+
+    fn condition<C: ColumnTrait>(
+        field: &QueryField<C>,
+        op: Op,
+        key: &str,
+        value: &str
+    ) -> ApiResult<Condition> {
+        let parse = |value: &str| {
+            parse_value(field.kind, value.trim()).ok_or_else(||
+                bad_request(t!("query.invalid_value", key = key, value = value))
+            )
+        };
+
+        let column = field.column;
+        let expr = match op {
+            // 等值过滤支持逗号分隔的多个取值
+            Op::Eq if value.contains(',') => {
+                let values = value.split(',').map(parse).collect::<ApiResult<Vec<_>>>()?;
+                column.is_in(values)
+            }
+            Op::Eq => column.eq(parse(value)?),
+            Op::Ne => column.ne(parse(value)?),
+            Op::Lt => column.lt(parse(value)?),
+            Op::Lte => column.lte(parse(value)?),
+            Op::Gt => column.gt(parse(value)?),
+            Op::Gte => column.gte(parse(value)?),
+        };
+
+        Ok(expr.into_condition())
+    }
+}
+
+// 文档中的列表查询参数，按实体的字段白名单生成
+pub struct ListParams<E>(PhantomData<E>);
+
+fn query_param(name: &str, schema_type: Type, description: String) -> Parameter {
+    ParameterBuilder::new()
+        .name(name)
+        .parameter_in(ParameterIn::Query)
+        .required(Required::False)
+        .description(Some(description))
+        .schema(Some(ObjectBuilder::new().schema_type(schema_type)))
+        .build()
+}
+
+impl<E: Queryable> IntoParams for ListParams<E> {
+    fn into_params(_: impl Fn() -> Option<ParameterIn>) -> Vec<Parameter> {
+        let fields = E::fields();
+        let names = fields
+            .iter()
+            .map(|field| field.name)
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        let mut params = vec![
+            query_param(
+                "sort",
+                Type::String,
+                format!("排序字段，逗号分隔，前缀 - 表示倒序，可选: {}", names)
+            )
+        ];
+
+        if !E::search_columns().is_empty() {
+            params.push(query_param("q", Type::String, "关键字模糊搜索".to_string()));
+        }
+
+        for field in &fields {
+            let (schema_type, description) = match field.kind {
+                FieldKind::Text | FieldKind::Enum(_) =>
+                    (Type::String, "等值过滤，逗号分隔多个取值，支持 _ne 后缀"),
+                FieldKind::Integer => (Type::Integer, "支持 _lt/_lte/_gt/_gte/_ne 后缀"),
+                FieldKind::Float | FieldKind::Decimal =>
+                    (Type::Number, "支持 _lt/_lte/_gt/_gte/_ne 后缀"),
+                FieldKind::DateTime =>
+                    (Type::String, "时间 (YYYY-MM-DD 或 YYYY-MM-DDTHH:MM:SS)，支持 _after/_before 后缀"),
+            };
+            params.push(query_param(field.name, schema_type, description.to_string()));
+        }
+
+        params
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fields() -> Vec<QueryField<&'static str>> {
+        vec![
+            QueryField::new("name", "name", FieldKind::Text),
+            QueryField::new("status", "status", FieldKind::Enum(|_| None)),
+            QueryField::new("battery", "battery", FieldKind::Integer),
+            QueryField::new("created_at", "created_at", FieldKind::DateTime),
+        ]
+    }
+
+    fn resolve(key: &str) -> Option<(&'static str, Op)> {
+        let fields = fields();
+        ListQuery::resolve(&fields, key).map(|(field, op)| (field.name, op))
+    }
+
+    #[test]
+    fn resolves_exact_names_as_equality() {
+        assert_eq!(resolve("name"), Some(("name", Op::Eq)));
+        assert_eq!(resolve("battery"), Some(("battery", Op::Eq)));
+        assert_eq!(resolve("unknown"), None);
+    }
+
+    #[test]
+    fn prefers_longer_suffixes() {
+        assert_eq!(resolve("battery_lte"), Some(("battery", Op::Lte)));
+        assert_eq!(resolve("battery_lt"), Some(("battery", Op::Lt)));
+        assert_eq!(resolve("battery_gte"), Some(("battery", Op::Gte)));
+        assert_eq!(resolve("battery_gt"), Some(("battery", Op::Gt)));
+        assert_eq!(resolve("battery_ne"), Some(("battery", Op::Ne)));
+    }
+
+    #[test]
+    fn maps_time_suffixes_to_datetime_fields() {
+        assert_eq!(resolve("created_after"), Some(("created_at", Op::Gte)));
+        assert_eq!(resolve("created_before"), Some(("created_at", Op::Lte)));
+        assert_eq!(resolve("created_at_after"), Some(("created_at", Op::Gte)));
+        assert_eq!(resolve("created_at_lt"), Some(("created_at", Op::Lt)));
+        // _after/_before 只用于时间字段
+        assert_eq!(resolve("battery_after"), None);
+    }
+
+    #[test]
+    fn allows_only_inequality_on_text_and_enum_fields() {
+        assert_eq!(resolve("name_ne"), Some(("name", Op::Ne)));
+        assert_eq!(resolve("status_ne"), Some(("status", Op::Ne)));
+        assert_eq!(resolve("name_lt"), None);
+        assert_eq!(resolve("status_gte"), None);
+        assert_eq!(resolve("status_before"), None);
+    }
+}
@@ -1,7 +1,12 @@
 use axum::response::{IntoResponse, Response};
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use utoipa::ToSchema;
 
-#[derive(Debug, Serialize, Deserialize)]
+use super::result::ErrorCode;
+
+// 统一响应结构，code 为 200 表示成功
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct ApiResponse<T> {
     pub code: i32,
     pub message: String,
@@ -18,9 +23,6 @@ impl<T> ApiResponse<T> {
         Self::new(200, String::from(message.as_ref()), data)
     }
 
-    pub fn err<M: AsRef<str>>(message: M) -> Self {
-        Self::new(0, String::from(message.as_ref()), None)
-    }
 }
 
 impl<T: Serialize> IntoResponse for ApiResponse<T> {
@@ -29,3 +31,21 @@ impl<T: Serialize> IntoResponse for ApiResponse<T> {
     }
 }
 
+
+// 失败时的响应结构，code 与 HTTP 状态码一致
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ErrorResponse {
+    pub code: u16,
+    pub error: ErrorCode,
+    pub message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub details: Option<Value>,
+}
+
+// 不带 data 的响应，仅用于接口文档
+#[allow(dead_code)]
+#[derive(ToSchema)]
+pub struct MessageResponse {
+    pub code: i32,
+    pub message: String,
+}
@@ -1,38 +1,179 @@
 use axum::http::StatusCode;
 use axum::response::{IntoResponse, Response};
 
-use super::response::ApiResponse;
+use sea_orm::{ ConnAcquireErr, DbErr, SqlErr };
+use serde::Serialize;
+use serde_json::Value;
+use utoipa::ToSchema;
+
+use super::response::{ ApiResponse, ErrorResponse };
+use super::validation;
+use crate::t;
 
 pub type ApiResult<T> = Result<T, ApiError>;
 
+// 供客户端判断错误类型的稳定错误码
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ErrorCode {
+    NotFound,
+    BadRequest,
+    Unauthorized,
+    Forbidden,
+    Conflict,
+    GeofenceViolation,
+    ValidationFailed,
+    InvalidReference,
+    ServiceUnavailable,
+    DatabaseError,
+    InternalError,
+}
+
+impl ErrorCode {
+    pub fn status_code(self) -> StatusCode {
+        match self {
+            ErrorCode::NotFound => StatusCode::NOT_FOUND,
+            ErrorCode::BadRequest => StatusCode::BAD_REQUEST,
+            ErrorCode::Unauthorized => StatusCode::UNAUTHORIZED,
+            ErrorCode::Forbidden => StatusCode::FORBIDDEN,
+            ErrorCode::Conflict | ErrorCode::GeofenceViolation => StatusCode::CONFLICT,
+            ErrorCode::ValidationFailed | ErrorCode::InvalidReference =>
+                StatusCode::UNPROCESSABLE_ENTITY,
+            ErrorCode::ServiceUnavailable => StatusCode::SERVICE_UNAVAILABLE,
+            ErrorCode::DatabaseError | ErrorCode::InternalError =>
+                StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+}
+
 #[derive(Debug, thiserror::Error)]
 pub enum ApiError {
-    #[error("Not Found")]
-    NotFound,
-    #[error("数据库异常: {0}")]
-    Database(#[from] sea_orm::DbErr),
     #[error("{0}")]
-    Biz(String),
+    NotFound(String),
+    #[error("{0}")]
+    BadRequest(String),
+    #[error("{0}")]
+    Unauthorized(String),
+    #[error("{0}")]
+    Forbidden(String),
+    #[error("{0}")]
+    Conflict(String),
+    // 任务航线进入禁飞区，附带违反的围栏列表
+    #[error("{0}")]
+    GeofenceViolation(String, Value),
+    #[error("请求参数校验失败")]
+    Validation(validator::ValidationErrors),
+    #[error("{0}")]
+    InvalidReference(String),
+    #[error("{0}")]
+    Unavailable(String),
+    #[error("数据库异常: {0}")]
+    Database(DbErr),
     #[error("错误: {0}")]
     Internal(#[from] anyhow::Error),
 }
 
 impl ApiError {
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            ApiError::NotFound(_) => ErrorCode::NotFound,
+            ApiError::BadRequest(_) => ErrorCode::BadRequest,
+            ApiError::Unauthorized(_) => ErrorCode::Unauthorized,
+            ApiError::Forbidden(_) => ErrorCode::Forbidden,
+            ApiError::Conflict(_) => ErrorCode::Conflict,
+            ApiError::GeofenceViolation(..) => ErrorCode::GeofenceViolation,
+            ApiError::Validation(_) => ErrorCode::ValidationFailed,
+            ApiError::InvalidReference(_) => ErrorCode::InvalidReference,
+            ApiError::Unavailable(_) => ErrorCode::ServiceUnavailable,
+            ApiError::Database(_) => ErrorCode::DatabaseError,
+            ApiError::Internal(_) => ErrorCode::InternalError,
+        }
+    }
+
     pub fn status_code(&self) -> StatusCode {
+        self.code().status_code()
+    }
+
+    // 按当前请求的语言生成提示，其余变体在构造时已取自语言包
+    pub fn message(&self) -> String {
         match self {
-            ApiError::NotFound => StatusCode::NOT_FOUND,
-            ApiError::Biz(_) => StatusCode::OK,
-            ApiError::Internal(_) | ApiError::Database(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            ApiError::Validation(_) => t!("error.validation_failed"),
+            ApiError::Database(err) => t!("error.database", detail = err),
+            ApiError::Internal(err) => t!("error.internal", detail = err),
+            _ => self.to_string(),
+        }
+    }
+
+    // 附加的错误详情：校验错误的逐字段信息或违反的围栏
+    pub fn details(&self) -> Option<Value> {
+        match self {
+            ApiError::Validation(errors) => serde_json::to_value(validation::field_errors(errors)).ok(),
+            ApiError::GeofenceViolation(_, violations) => Some(violations.clone()),
+            _ => None,
+        }
+    }
+
+    pub fn to_body(&self) -> ErrorResponse {
+        ErrorResponse {
+            code: self.status_code().as_u16(),
+            error: self.code(),
+            message: self.message(),
+            details: self.details(),
+        }
+    }
+}
+
+// 按错误类型区分数据库异常，避免约束冲突等可预期的错误都变成 500
+impl From<DbErr> for ApiError {
+    fn from(err: DbErr) -> Self {
+        match err.sql_err() {
+            Some(SqlErr::UniqueConstraintViolation(_)) => {
+                return ApiError::Conflict(t!("error.conflict"));
+            }
+            Some(SqlErr::ForeignKeyConstraintViolation(_)) => {
+                return ApiError::InvalidReference(t!("error.invalid_reference"));
+            }
+            _ => {}
+        }
+
+        match err {
+            DbErr::RecordNotFound(_) | DbErr::RecordNotUpdated =>
+                ApiError::NotFound(t!("error.record_not_found")),
+            DbErr::ConnectionAcquire(ConnAcquireErr::Timeout) =>
+                ApiError::Unavailable(t!("error.database_busy")),
+            err => ApiError::Database(err),
         }
     }
 }
 
 impl IntoResponse for ApiError {
     fn into_response(self) -> Response {
-        let status_code = self.status_code();
+        let body = self.to_body();
 
-        let body = axum::Json(ApiResponse::<()>::err(self.to_string()));
-
-        (status_code, body).into_response()
+        let mut response = (self.status_code(), axum::Json(&body)).into_response();
+        // 保留原始错误体，供兼容旧格式的中间件改写
+        response.extensions_mut().insert(body);
+        response
     }
 }
+
+// 兼容旧版客户端：错误统一返回 code 为 0 的响应体，除鉴权和服务端错误外 HTTP 状态码均为 200
+pub async fn legacy_envelope(response: Response) -> Response {
+    let Some(body) = response.extensions().get::<ErrorResponse>().cloned() else {
+        return response;
+    };
+
+    let status = response.status();
+    let status = if
+        status.is_server_error() ||
+        status == StatusCode::UNAUTHORIZED ||
+        status == StatusCode::FORBIDDEN
+    {
+        status
+    } else {
+        StatusCode::OK
+    };
+
+    let body = ApiResponse::new(0, body.message, body.details);
+    (status, axum::Json(body)).into_response()
+}
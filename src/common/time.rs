@@ -0,0 +1,6 @@
+use chrono::{ NaiveDateTime, SubsecRound, Utc };
+
+// 当前 UTC 时间，截断到秒以匹配 DATETIME 列的精度
+pub fn now() -> NaiveDateTime {
+    Utc::now().naive_utc().trunc_subsecs(0)
+}
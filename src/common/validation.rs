@@ -0,0 +1,161 @@
+use std::collections::BTreeMap;
+use std::error::Error;
+
+use axum::Json;
+use axum::extract::rejection::{ JsonRejection, PathRejection, QueryRejection };
+use axum::extract::{ FromRequest, FromRequestParts, Path, Query, Request };
+use axum::http::request::Parts;
+use axum_valid::{ Valid, ValidRejection };
+use rust_decimal::Decimal;
+use serde::de::DeserializeOwned;
+use validator::{ Validate, ValidationError, ValidationErrors, ValidationErrorsKind };
+
+use super::result::ApiError;
+use crate::t;
+
+// 反序列化后按 validator 规则校验的 JSON 请求体，校验失败返回 422 和逐字段的错误信息
+pub struct ValidJson<T>(pub T);
+
+impl<S, T> FromRequest<S> for ValidJson<T> where S: Send + Sync, T: DeserializeOwned + Validate {
+    type Rejection = ApiError;
+
+    async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+        match Valid::<Json<T>>::from_request(req, state).await {
+            Ok(Valid(Json(data))) => Ok(ValidJson(data)),
+            Err(ValidRejection::Valid(errors)) => Err(ApiError::Validation(errors)),
+            Err(ValidRejection::Inner(rejection)) => Err(json_rejection(rejection)),
+        }
+    }
+}
+
+// 框架给出的解析错误是英文的，说明部分按请求语言输出，只附带具体原因
+fn reason(error: &dyn Error) -> String {
+    error.source().map_or_else(|| error.to_string(), ToString::to_string)
+}
+
+fn json_rejection(rejection: JsonRejection) -> ApiError {
+    let message = match rejection {
+        JsonRejection::MissingJsonContentType(_) => t!("request.json_content_type"),
+        JsonRejection::JsonSyntaxError(e) => t!("request.json_syntax", detail = reason(&e)),
+        JsonRejection::JsonDataError(e) => t!("request.json_data", detail = reason(&e)),
+        other => t!("request.body_unreadable", detail = reason(&other)),
+    };
+    ApiError::BadRequest(message)
+}
+
+fn query_rejection(rejection: QueryRejection) -> ApiError {
+    ApiError::BadRequest(t!("request.invalid_query", detail = reason(&rejection)))
+}
+
+fn path_rejection(rejection: PathRejection) -> ApiError {
+    let detail = match rejection {
+        PathRejection::FailedToDeserializePathParams(e) => e.kind().to_string(),
+        other => reason(&other),
+    };
+    ApiError::BadRequest(t!("request.invalid_path", detail = detail))
+}
+
+// 查询参数，解析失败时与请求体一样返回统一的错误格式
+pub struct ValidQuery<T>(pub T);
+
+impl<S, T> FromRequestParts<S> for ValidQuery<T> where S: Send + Sync, T: DeserializeOwned {
+    type Rejection = ApiError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        match Query::<T>::from_request_parts(parts, state).await {
+            Ok(Query(data)) => Ok(ValidQuery(data)),
+            Err(rejection) => Err(query_rejection(rejection)),
+        }
+    }
+}
+
+// 路径参数，解析失败时返回统一的错误格式
+pub struct ValidPath<T>(pub T);
+
+impl<S, T> FromRequestParts<S> for ValidPath<T> where S: Send + Sync, T: DeserializeOwned + Send {
+    type Rejection = ApiError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        match Path::<T>::from_request_parts(parts, state).await {
+            Ok(Path(data)) => Ok(ValidPath(data)),
+            Err(rejection) => Err(path_rejection(rejection)),
+        }
+    }
+}
+
+// 纬度范围 [-90, 90]
+pub fn latitude(value: &Decimal) -> Result<(), ValidationError> {
+    if value.abs() > Decimal::from(90) {
+        return Err(ValidationError::new("latitude"));
+    }
+    Ok(())
+}
+
+// 经度范围 [-180, 180]
+pub fn longitude(value: &Decimal) -> Result<(), ValidationError> {
+    if value.abs() > Decimal::from(180) {
+        return Err(ValidationError::new("longitude"));
+    }
+    Ok(())
+}
+
+fn param(error: &ValidationError, name: &str) -> Option<String> {
+    error.params.get(name).map(|value| value.to_string())
+}
+
+// 未指定 message 的规则按错误码和参数生成提示
+fn describe(error: &ValidationError) -> String {
+    if let Some(message) = &error.message {
+        return message.to_string();
+    }
+
+    if error.code == "range" && let Some(min) = param(error, "exclusive_min") {
+        return match param(error, "max") {
+            Some(max) => t!("validation.range_above_max", min = min, max = max),
+            None => t!("validation.range_above", min = min),
+        };
+    }
+
+    let (min, max) = (param(error, "min"), param(error, "max"));
+    match (error.code.as_ref(), min, max) {
+        ("latitude", ..) => t!("validation.latitude"),
+        ("longitude", ..) => t!("validation.longitude"),
+        ("geofence_circle", ..) => t!("validation.geofence_circle"),
+        ("geofence_polygon", ..) => t!("validation.geofence_polygon"),
+        ("time_window", ..) => t!("validation.time_window"),
+        ("cron", ..) => t!("validation.cron"),
+        ("template_drone", ..) => t!("validation.template_drone"),
+        ("length", Some(min), Some(max)) => t!("validation.length_between", min = min, max = max),
+        ("length", Some(min), None) if min == "1" => t!("validation.required"),
+        ("length", Some(min), None) => t!("validation.length_min", min = min),
+        ("length", None, Some(max)) => t!("validation.length_max", max = max),
+        ("range", Some(min), Some(max)) => t!("validation.range_between", min = min, max = max),
+        ("range", Some(min), None) => t!("validation.range_min", min = min),
+        ("range", None, Some(max)) => t!("validation.range_max", max = max),
+        (code, ..) => t!("validation.failed", code = code),
+    }
+}
+
+fn collect(errors: &ValidationErrors, prefix: &str, map: &mut BTreeMap<String, Vec<String>>) {
+    for (field, kind) in errors.errors() {
+        let path = if prefix.is_empty() { field.to_string() } else { format!("{}.{}", prefix, field) };
+        match kind {
+            ValidationErrorsKind::Field(errors) => {
+                map.entry(path).or_default().extend(errors.iter().map(describe));
+            }
+            ValidationErrorsKind::Struct(errors) => collect(errors, &path, map),
+            ValidationErrorsKind::List(items) => {
+                for (index, errors) in items {
+                    collect(errors, &format!("{}[{}]", path, index), map);
+                }
+            }
+        }
+    }
+}
+
+// 将校验错误展开为 字段路径 -> 错误信息 的映射，嵌套字段形如 records[0].lat
+pub fn field_errors(errors: &ValidationErrors) -> BTreeMap<String, Vec<String>> {
+    let mut map = BTreeMap::new();
+    collect(errors, "", &mut map);
+    map
+}
@@ -1,12 +1,12 @@
-//! `SeaORM` Entity, @generated by sea-orm-codegen 1.1.19
-
 use super::sea_orm_active_enums::Status;
 use sea_orm::entity::prelude::*;
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 
-#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize, ToSchema)]
 #[sea_orm(table_name = "drones")]
 #[serde(rename_all = "camelCase")]
+#[schema(as = Drone)]
 pub struct Model {
     #[sea_orm(primary_key, auto_increment = false)]
     pub drone_id: String,
@@ -15,12 +15,37 @@ pub struct Model {
     pub status: Status,
     pub battery: u8,
     pub activate: i8,
+    #[sea_orm(column_type = "Decimal(Some((9, 6)))", nullable)]
+    pub last_known_lat: Option<Decimal>,
+    #[sea_orm(column_type = "Decimal(Some((9, 6)))", nullable)]
+    pub last_known_lng: Option<Decimal>,
+    #[sea_orm(column_type = "Float", nullable)]
+    pub last_known_altitude: Option<f32>,
+    pub last_seen_at: Option<DateTime>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
 pub enum Relation {
+    #[sea_orm(has_many = "super::maintenance_records::Entity")]
+    MaintenanceRecords,
+    #[sea_orm(has_many = "super::mission_templates::Entity")]
+    MissionTemplates,
     #[sea_orm(has_many = "super::missions::Entity")]
     Missions,
+    #[sea_orm(has_many = "super::telemetry::Entity")]
+    Telemetry,
+}
+
+impl Related<super::maintenance_records::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::MaintenanceRecords.def()
+    }
+}
+
+impl Related<super::mission_templates::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::MissionTemplates.def()
+    }
 }
 
 impl Related<super::missions::Entity> for Entity {
@@ -29,4 +54,10 @@ impl Related<super::missions::Entity> for Entity {
     }
 }
 
+impl Related<super::telemetry::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Telemetry.def()
+    }
+}
+
 impl ActiveModelBehavior for ActiveModel {}
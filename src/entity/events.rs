@@ -1,12 +1,12 @@
-//! `SeaORM` Entity, @generated by sea-orm-codegen 1.1.19
-
 use super::sea_orm_active_enums::EventType;
 use sea_orm::entity::prelude::*;
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 
-#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize, ToSchema)]
 #[sea_orm(table_name = "events")]
 #[serde(rename_all = "camelCase")]
+#[schema(as = Event)]
 pub struct Model {
     #[sea_orm(primary_key, auto_increment = false)]
     pub event_id: String,
@@ -0,0 +1,56 @@
+use super::sea_orm_active_enums::{ GeofenceKind, GeofenceShape };
+use sea_orm::entity::prelude::*;
+use sea_orm::{ ActiveValue, FromJsonQueryResult };
+use serde::{ Deserialize, Serialize };
+use utoipa::ToSchema;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize, ToSchema)]
+#[sea_orm(table_name = "geofences")]
+#[serde(rename_all = "camelCase")]
+#[schema(as = Geofence)]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub geofence_id: String,
+    pub name: String,
+    pub shape: GeofenceShape,
+    pub kind: GeofenceKind,
+    #[sea_orm(column_type = "Decimal(Some((9, 6)))", nullable)]
+    pub center_lat: Option<Decimal>,
+    #[sea_orm(column_type = "Decimal(Some((9, 6)))", nullable)]
+    pub center_lng: Option<Decimal>,
+    #[sea_orm(column_type = "Float", nullable)]
+    pub radius: Option<f32>,
+    #[sea_orm(column_type = "Json", nullable)]
+    pub vertices: Option<Vertices>,
+    pub active_from: Option<DateTime>,
+    pub active_until: Option<DateTime>,
+    pub created_by: String,
+    pub created_at: DateTime,
+    pub updated_at: Option<DateTime>,
+}
+
+// 多边形顶点，按顺序首尾相连
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, FromJsonQueryResult, ToSchema)]
+pub struct Vertices(pub Vec<Vertex>);
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct Vertex {
+    pub lat: Decimal,
+    pub lng: Decimal,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+#[async_trait::async_trait]
+impl ActiveModelBehavior for ActiveModel {
+    // SQLite 不支持 ON UPDATE CURRENT_TIMESTAMP，由应用层维护更新时间
+    async fn before_save<C>(mut self, _db: &C, insert: bool) -> Result<Self, DbErr>
+        where C: ConnectionTrait
+    {
+        if !insert {
+            self.updated_at = ActiveValue::set(Some(crate::common::time::now()));
+        }
+        Ok(self)
+    }
+}
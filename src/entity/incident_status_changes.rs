@@ -0,0 +1,38 @@
+use super::sea_orm_active_enums::IncidentStatus;
+use sea_orm::entity::prelude::*;
+use serde::{ Deserialize, Serialize };
+use utoipa::ToSchema;
+
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Serialize, Deserialize, ToSchema)]
+#[sea_orm(table_name = "incident_status_changes")]
+#[serde(rename_all = "camelCase")]
+#[schema(as = IncidentStatusChange)]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub change_id: i64,
+    pub incident_id: String,
+    pub from_status: IncidentStatus,
+    pub to_status: IncidentStatus,
+    pub changed_by: Option<String>,
+    pub created_at: DateTime,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::incidents::Entity",
+        from = "Column::IncidentId",
+        to = "super::incidents::Column::IncidentId",
+        on_update = "NoAction",
+        on_delete = "Cascade"
+    )]
+    Incidents,
+}
+
+impl Related<super::incidents::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Incidents.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -1,12 +1,13 @@
-//! `SeaORM` Entity, @generated by sea-orm-codegen 1.1.19
-
 use super::sea_orm_active_enums::IncidentStatus;
+use sea_orm::ActiveValue;
 use sea_orm::entity::prelude::*;
 use serde::{ Deserialize, Serialize };
+use utoipa::ToSchema;
 
-#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize, ToSchema)]
 #[sea_orm(table_name = "incidents")]
 #[serde(rename_all = "camelCase")]
+#[schema(as = Incident)]
 pub struct Model {
     #[sea_orm(primary_key, auto_increment = false)]
     pub incident_id: String,
@@ -28,6 +29,10 @@ pub struct Model {
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
 pub enum Relation {
+    #[sea_orm(has_many = "super::incident_status_changes::Entity")]
+    IncidentStatusChanges,
+    #[sea_orm(has_many = "super::missions::Entity")]
+    Missions,
     #[sea_orm(
         belongs_to = "super::users::Entity",
         from = "Column::CreatedBy",
@@ -38,10 +43,33 @@ pub enum Relation {
     Users,
 }
 
+impl Related<super::incident_status_changes::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::IncidentStatusChanges.def()
+    }
+}
+
+impl Related<super::missions::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Missions.def()
+    }
+}
+
 impl Related<super::users::Entity> for Entity {
     fn to() -> RelationDef {
         Relation::Users.def()
     }
 }
 
-impl ActiveModelBehavior for ActiveModel {}
+#[async_trait::async_trait]
+impl ActiveModelBehavior for ActiveModel {
+    // SQLite 不支持 ON UPDATE CURRENT_TIMESTAMP，由应用层维护更新时间
+    async fn before_save<C>(mut self, _db: &C, insert: bool) -> Result<Self, DbErr>
+        where C: ConnectionTrait
+    {
+        if !insert {
+            self.updated_at = ActiveValue::set(Some(crate::common::time::now()));
+        }
+        Ok(self)
+    }
+}
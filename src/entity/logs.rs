@@ -1,12 +1,12 @@
-//! `SeaORM` Entity, @generated by sea-orm-codegen 1.1.19
-
 use super::sea_orm_active_enums::LogType;
 use sea_orm::entity::prelude::*;
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 
-#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize, ToSchema)]
 #[sea_orm(table_name = "logs")]
 #[serde(rename_all = "camelCase")]
+#[schema(as = Log)]
 pub struct Model {
     #[sea_orm(primary_key)]
     pub log_id: i32,
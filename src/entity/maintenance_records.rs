@@ -0,0 +1,54 @@
+use super::sea_orm_active_enums::MaintenanceType;
+use sea_orm::entity::prelude::*;
+use sea_orm::FromJsonQueryResult;
+use serde::{ Deserialize, Serialize };
+use utoipa::ToSchema;
+
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Serialize, Deserialize, ToSchema)]
+#[sea_orm(table_name = "maintenance_records")]
+#[serde(rename_all = "camelCase")]
+#[schema(as = MaintenanceRecord)]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub record_id: i64,
+    pub drone_id: String,
+    pub maintenance_type: MaintenanceType,
+    pub technician: String,
+    #[sea_orm(column_type = "Text", nullable)]
+    pub notes: Option<String>,
+    #[sea_orm(column_type = "Json", nullable)]
+    pub parts: Option<Parts>,
+    pub started_at: DateTime,
+    pub ended_at: Option<DateTime>,
+    pub created_by: Option<String>,
+}
+
+// 维护中更换的零件
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, FromJsonQueryResult, ToSchema)]
+pub struct Parts(pub Vec<Part>);
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct Part {
+    pub name: String,
+    pub quantity: u32,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::drones::Entity",
+        from = "Column::DroneId",
+        to = "super::drones::Column::DroneId",
+        on_update = "NoAction",
+        on_delete = "Cascade"
+    )]
+    Drones,
+}
+
+impl Related<super::drones::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Drones.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
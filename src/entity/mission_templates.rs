@@ -0,0 +1,97 @@
+use super::sea_orm_active_enums::WaypointAction;
+use sea_orm::entity::prelude::*;
+use sea_orm::{ ActiveValue, FromJsonQueryResult };
+use serde::{ Deserialize, Serialize };
+use utoipa::ToSchema;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize, ToSchema)]
+#[sea_orm(table_name = "mission_templates")]
+#[serde(rename_all = "camelCase")]
+#[schema(as = MissionTemplate)]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub template_id: String,
+    pub name: String,
+    pub user_id: String,
+    pub drone_id: Option<String>,
+    pub drone_model: Option<String>,
+    #[sea_orm(column_type = "Decimal(Some((9, 6)))")]
+    pub target_lat: Decimal,
+    #[sea_orm(column_type = "Decimal(Some((9, 6)))")]
+    pub target_lng: Decimal,
+    #[sea_orm(column_type = "Json", nullable)]
+    pub waypoints: Option<TemplateWaypoints>,
+    pub cron: String,
+    pub paused: bool,
+    pub last_run_at: Option<DateTime>,
+    pub next_run_at: Option<DateTime>,
+    pub created_at: DateTime,
+    pub updated_at: Option<DateTime>,
+}
+
+// 每次生成任务时复制到 mission_waypoints 的航线，按数组顺序编号
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, FromJsonQueryResult, ToSchema)]
+pub struct TemplateWaypoints(pub Vec<TemplateWaypoint>);
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TemplateWaypoint {
+    pub lat: Decimal,
+    pub lng: Decimal,
+    pub altitude: f32,
+    pub loiter_seconds: i32,
+    pub action: WaypointAction,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::drones::Entity",
+        from = "Column::DroneId",
+        to = "super::drones::Column::DroneId",
+        on_update = "NoAction",
+        on_delete = "SetNull"
+    )]
+    Drones,
+    #[sea_orm(
+        belongs_to = "super::users::Entity",
+        from = "Column::UserId",
+        to = "super::users::Column::UserId",
+        on_update = "NoAction",
+        on_delete = "Cascade"
+    )]
+    Users,
+    #[sea_orm(has_many = "super::missions::Entity")]
+    Missions,
+}
+
+impl Related<super::drones::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Drones.def()
+    }
+}
+
+impl Related<super::users::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Users.def()
+    }
+}
+
+impl Related<super::missions::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Missions.def()
+    }
+}
+
+#[async_trait::async_trait]
+impl ActiveModelBehavior for ActiveModel {
+    // SQLite 不支持 ON UPDATE CURRENT_TIMESTAMP，由应用层维护更新时间
+    async fn before_save<C>(mut self, _db: &C, insert: bool) -> Result<Self, DbErr>
+        where C: ConnectionTrait
+    {
+        if !insert {
+            self.updated_at = ActiveValue::set(Some(crate::common::time::now()));
+        }
+        Ok(self)
+    }
+}
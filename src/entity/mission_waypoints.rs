@@ -0,0 +1,44 @@
+use super::sea_orm_active_enums::WaypointAction;
+use sea_orm::entity::prelude::*;
+use serde::{ Deserialize, Serialize };
+use utoipa::ToSchema;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize, ToSchema)]
+#[sea_orm(table_name = "mission_waypoints")]
+#[serde(rename_all = "camelCase")]
+#[schema(as = MissionWaypoint)]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub waypoint_id: i64,
+    pub mission_id: String,
+    pub seq: i32,
+    #[sea_orm(column_type = "Decimal(Some((9, 6)))")]
+    pub lat: Decimal,
+    #[sea_orm(column_type = "Decimal(Some((9, 6)))")]
+    pub lng: Decimal,
+    #[sea_orm(column_type = "Float")]
+    pub altitude: f32,
+    pub loiter_seconds: i32,
+    pub action: WaypointAction,
+    pub created_at: DateTime,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::missions::Entity",
+        from = "Column::MissionId",
+        to = "super::missions::Column::MissionId",
+        on_update = "NoAction",
+        on_delete = "Cascade"
+    )]
+    Missions,
+}
+
+impl Related<super::missions::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Missions.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -1,12 +1,12 @@
-//! `SeaORM` Entity, @generated by sea-orm-codegen 1.1.19
-
 use super::sea_orm_active_enums::Status;
 use sea_orm::entity::prelude::*;
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 
-#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize, ToSchema)]
 #[sea_orm(table_name = "missions")]
 #[serde(rename_all = "camelCase")]
+#[schema(as = Mission)]
 pub struct Model {
     #[sea_orm(primary_key, auto_increment = false)]
     pub mission_id: String,
@@ -20,6 +20,8 @@ pub struct Model {
     pub created_at: DateTime,
     pub started_at: Option<DateTime>,
     pub completed_at: Option<DateTime>,
+    pub incident_id: Option<String>,
+    pub template_id: Option<String>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -34,6 +36,26 @@ pub enum Relation {
     Drones,
     #[sea_orm(has_many = "super::events::Entity")]
     Events,
+    #[sea_orm(
+        belongs_to = "super::incidents::Entity",
+        from = "Column::IncidentId",
+        to = "super::incidents::Column::IncidentId",
+        on_update = "NoAction",
+        on_delete = "NoAction"
+    )]
+    Incidents,
+    #[sea_orm(
+        belongs_to = "super::mission_templates::Entity",
+        from = "Column::TemplateId",
+        to = "super::mission_templates::Column::TemplateId",
+        on_update = "NoAction",
+        on_delete = "NoAction"
+    )]
+    MissionTemplates,
+    #[sea_orm(has_many = "super::mission_waypoints::Entity")]
+    MissionWaypoints,
+    #[sea_orm(has_many = "super::telemetry::Entity")]
+    Telemetry,
     #[sea_orm(
         belongs_to = "super::users::Entity",
         from = "Column::UserId",
@@ -56,6 +78,30 @@ impl Related<super::events::Entity> for Entity {
     }
 }
 
+impl Related<super::incidents::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Incidents.def()
+    }
+}
+
+impl Related<super::mission_templates::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::MissionTemplates.def()
+    }
+}
+
+impl Related<super::mission_waypoints::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::MissionWaypoints.def()
+    }
+}
+
+impl Related<super::telemetry::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Telemetry.def()
+    }
+}
+
 impl Related<super::users::Entity> for Entity {
     fn to() -> RelationDef {
         Relation::Users.def()
@@ -1,11 +1,15 @@
-//! `SeaORM` Entity, @generated by sea-orm-codegen 1.1.19
-
 pub mod prelude;
 
 pub mod drones;
 pub mod events;
+pub mod geofences;
+pub mod incident_status_changes;
 pub mod incidents;
 pub mod logs;
+pub mod maintenance_records;
+pub mod mission_templates;
+pub mod mission_waypoints;
 pub mod missions;
 pub mod sea_orm_active_enums;
+pub mod telemetry;
 pub mod users;
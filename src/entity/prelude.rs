@@ -1,8 +1,12 @@
-//! `SeaORM` Entity, @generated by sea-orm-codegen 1.1.19
-
 pub use super::drones::Entity as Drones;
 pub use super::events::Entity as Events;
+pub use super::geofences::Entity as Geofences;
+pub use super::incident_status_changes::Entity as IncidentStatusChanges;
 pub use super::incidents::Entity as Incidents;
 pub use super::logs::Entity as Logs;
+pub use super::maintenance_records::Entity as MaintenanceRecords;
+pub use super::mission_templates::Entity as MissionTemplates;
+pub use super::mission_waypoints::Entity as MissionWaypoints;
 pub use super::missions::Entity as Missions;
+pub use super::telemetry::Entity as Telemetry;
 pub use super::users::Entity as Users;
@@ -1,9 +1,8 @@
-//! `SeaORM` Entity, @generated by sea-orm-codegen 1.1.19
-
 use sea_orm::entity::prelude::*;
 use serde::{ Deserialize, Serialize };
+use utoipa::ToSchema;
 
-#[derive(Debug, Clone, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize, ToSchema)]
 #[sea_orm(rs_type = "String", db_type = "Enum", enum_name = "event_type")]
 pub enum EventType {
     #[sea_orm(string_value = "takeoff")]
@@ -19,7 +18,7 @@ pub enum EventType {
     #[sea_orm(string_value = "error")]
     Error,
 }
-#[derive(Debug, Clone, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize, ToSchema)]
 #[sea_orm(rs_type = "String", db_type = "Enum", enum_name = "log_type")]
 pub enum LogType {
     #[sea_orm(string_value = "INFO")]
@@ -29,7 +28,21 @@ pub enum LogType {
     #[sea_orm(string_value = "ERROR")]
     Error,
 }
-#[derive(Debug, Clone, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize, ToSchema)]
+#[sea_orm(rs_type = "String", db_type = "Enum", enum_name = "maintenance_type")]
+pub enum MaintenanceType {
+    #[sea_orm(string_value = "inspection")]
+    Inspection,
+    #[sea_orm(string_value = "repair")]
+    Repair,
+    #[sea_orm(string_value = "battery")]
+    Battery,
+    #[sea_orm(string_value = "firmware")]
+    Firmware,
+    #[sea_orm(string_value = "other")]
+    Other,
+}
+#[derive(Debug, Clone, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize, ToSchema)]
 #[sea_orm(rs_type = "String", db_type = "Enum", enum_name = "role")]
 pub enum Role {
     #[sea_orm(string_value = "superadmin")]
@@ -39,7 +52,7 @@ pub enum Role {
     #[sea_orm(string_value = "user")]
     User,
 }
-#[derive(Debug, Clone, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize, ToSchema)]
 #[sea_orm(rs_type = "String", db_type = "Enum", enum_name = "status")]
 pub enum Status {
     #[sea_orm(string_value = "idle")]
@@ -52,9 +65,27 @@ pub enum Status {
     Completed,
     #[sea_orm(string_value = "error")]
     Error,
+    #[sea_orm(string_value = "maintenance")]
+    Maintenance,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize, ToSchema)]
+#[sea_orm(rs_type = "String", db_type = "Enum", enum_name = "geofence_kind")]
+pub enum GeofenceKind {
+    #[sea_orm(string_value = "inclusion")]
+    Inclusion,
+    #[sea_orm(string_value = "exclusion")]
+    Exclusion,
+}
+#[derive(Debug, Clone, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize, ToSchema)]
+#[sea_orm(rs_type = "String", db_type = "Enum", enum_name = "geofence_shape")]
+pub enum GeofenceShape {
+    #[sea_orm(string_value = "circle")]
+    Circle,
+    #[sea_orm(string_value = "polygon")]
+    Polygon,
+}
+#[derive(Debug, Clone, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize, ToSchema)]
 #[sea_orm(rs_type = "String", db_type = "Enum", enum_name = "incident_status")]
 pub enum IncidentStatus {
     #[sea_orm(string_value = "open")]
@@ -64,3 +95,17 @@ pub enum IncidentStatus {
     #[sea_orm(string_value = "closed")]
     Closed,
 }
+#[derive(Debug, Clone, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize, ToSchema)]
+#[sea_orm(rs_type = "String", db_type = "Enum", enum_name = "waypoint_action")]
+pub enum WaypointAction {
+    #[sea_orm(string_value = "fly_through")]
+    FlyThrough,
+    #[sea_orm(string_value = "hover")]
+    Hover,
+    #[sea_orm(string_value = "photo")]
+    Photo,
+    #[sea_orm(string_value = "video")]
+    Video,
+    #[sea_orm(string_value = "land")]
+    Land,
+}
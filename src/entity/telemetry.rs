@@ -0,0 +1,61 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize, ToSchema)]
+#[sea_orm(table_name = "telemetry")]
+#[serde(rename_all = "camelCase")]
+#[schema(as = Telemetry)]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub telemetry_id: i64,
+    pub drone_id: String,
+    pub mission_id: Option<String>,
+    #[sea_orm(column_type = "Decimal(Some((9, 6)))")]
+    pub lat: Decimal,
+    #[sea_orm(column_type = "Decimal(Some((9, 6)))")]
+    pub lng: Decimal,
+    #[sea_orm(column_type = "Float")]
+    pub altitude: f32,
+    #[sea_orm(column_type = "Float", nullable)]
+    pub heading: Option<f32>,
+    #[sea_orm(column_type = "Float", nullable)]
+    pub speed: Option<f32>,
+    pub battery: u8,
+    pub recorded_at: DateTime,
+    pub created_at: DateTime,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::drones::Entity",
+        from = "Column::DroneId",
+        to = "super::drones::Column::DroneId",
+        on_update = "NoAction",
+        on_delete = "NoAction"
+    )]
+    Drones,
+    #[sea_orm(
+        belongs_to = "super::missions::Entity",
+        from = "Column::MissionId",
+        to = "super::missions::Column::MissionId",
+        on_update = "NoAction",
+        on_delete = "NoAction"
+    )]
+    Missions,
+}
+
+impl Related<super::drones::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Drones.def()
+    }
+}
+
+impl Related<super::missions::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Missions.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
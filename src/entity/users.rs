@@ -1,16 +1,18 @@
-//! `SeaORM` Entity, @generated by sea-orm-codegen 1.1.19
-
 use super::sea_orm_active_enums::Role;
+use sea_orm::ActiveValue;
 use sea_orm::entity::prelude::*;
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 
-#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize, ToSchema)]
 #[sea_orm(table_name = "users")]
 #[serde(rename_all = "camelCase")]
+#[schema(as = User)]
 pub struct Model {
     #[sea_orm(primary_key, auto_increment = false)]
     pub user_id: String,
     pub name: String,
+    #[serde(skip_serializing)]
     pub password: String,
     pub role: Role,
     pub created_at: Option<DateTime>,
@@ -21,6 +23,8 @@ pub struct Model {
 pub enum Relation {
     #[sea_orm(has_many = "super::incidents::Entity")]
     Incidents,
+    #[sea_orm(has_many = "super::mission_templates::Entity")]
+    MissionTemplates,
     #[sea_orm(has_many = "super::missions::Entity")]
     Missions,
 }
@@ -31,10 +35,27 @@ impl Related<super::incidents::Entity> for Entity {
     }
 }
 
+impl Related<super::mission_templates::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::MissionTemplates.def()
+    }
+}
+
 impl Related<super::missions::Entity> for Entity {
     fn to() -> RelationDef {
         Relation::Missions.def()
     }
 }
 
-impl ActiveModelBehavior for ActiveModel {}
+#[async_trait::async_trait]
+impl ActiveModelBehavior for ActiveModel {
+    // SQLite 不支持 ON UPDATE CURRENT_TIMESTAMP，由应用层维护更新时间
+    async fn before_save<C>(mut self, _db: &C, insert: bool) -> Result<Self, DbErr>
+        where C: ConnectionTrait
+    {
+        if !insert {
+            self.updated_at = ActiveValue::set(Some(crate::common::time::now()));
+        }
+        Ok(self)
+    }
+}
@@ -0,0 +1,120 @@
+use sea_orm::prelude::{ DateTime, Decimal };
+use serde::Serialize;
+use tokio::sync::broadcast;
+
+use crate::entity::{ events, logs };
+use crate::entity::sea_orm_active_enums::{ EventType, Status };
+
+// 广播通道容量，订阅者落后超过该数量的消息会被丢弃；需容纳多次最大批量的遥测上报
+pub const HUB_CAPACITY: usize = 16384;
+
+// 新日志的广播通道容量
+const LOG_CAPACITY: usize = 256;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TelemetryUpdate {
+    pub drone_id: String,
+    pub mission_id: Option<String>,
+    pub lat: Decimal,
+    pub lng: Decimal,
+    pub altitude: f32,
+    pub heading: Option<f32>,
+    pub speed: Option<f32>,
+    pub battery: u8,
+    pub recorded_at: DateTime,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MissionStatusChange {
+    pub mission_id: String,
+    pub drone_id: String,
+    // 任务所属用户，普通用户只会收到自己任务的状态变化
+    pub user_id: String,
+    pub from: Status,
+    pub to: Status,
+    pub changed_at: DateTime,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EventCreated {
+    pub drone_id: String,
+    // 事件所属任务的用户，普通用户只会收到自己任务的事件
+    pub user_id: String,
+    #[serde(flatten)]
+    pub event: events::Model,
+}
+
+// 推送给实时订阅者的消息
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", content = "data", rename_all = "camelCase")]
+pub enum HubMessage {
+    Telemetry(TelemetryUpdate),
+    MissionStatus(MissionStatusChange),
+    Event(EventCreated),
+}
+
+impl HubMessage {
+    pub fn drone_id(&self) -> Option<&str> {
+        match self {
+            HubMessage::Telemetry(update) => Some(&update.drone_id),
+            HubMessage::MissionStatus(change) => Some(&change.drone_id),
+            HubMessage::Event(created) => Some(&created.drone_id),
+        }
+    }
+
+    pub fn mission_id(&self) -> Option<&str> {
+        match self {
+            HubMessage::Telemetry(update) => update.mission_id.as_deref(),
+            HubMessage::MissionStatus(change) => Some(&change.mission_id),
+            HubMessage::Event(created) => Some(&created.event.mission_id),
+        }
+    }
+
+    pub fn event_type(&self) -> Option<&EventType> {
+        match self {
+            HubMessage::Event(created) => Some(&created.event.event_type),
+            _ => None,
+        }
+    }
+}
+
+// 进程内的消息广播中心，实时推送和日志流各用一个通道
+#[derive(Debug, Clone)]
+pub struct Hub {
+    sender: broadcast::Sender<HubMessage>,
+    logs: broadcast::Sender<logs::Model>,
+}
+
+impl Hub {
+    pub fn new() -> Self {
+        let (sender, _) = broadcast::channel(HUB_CAPACITY);
+        let (logs, _) = broadcast::channel(LOG_CAPACITY);
+        Hub { sender, logs }
+    }
+
+    // 没有订阅者时消息直接丢弃
+    pub fn publish(&self, message: HubMessage) {
+        let _ = self.sender.send(message);
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<HubMessage> {
+        self.sender.subscribe()
+    }
+
+    pub fn publish_log(&self, log: logs::Model) {
+        let _ = self.logs.send(log);
+    }
+
+    pub fn subscribe_logs(&self) -> broadcast::Receiver<logs::Model> {
+        self.logs.subscribe()
+    }
+}
+
+impl Default for Hub {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,239 @@
+//! 初始表结构，与 `scripts/init.sql` 保持一致，但只使用 MySQL 与 SQLite 都支持的写法
+
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[derive(DeriveIden)]
+enum Users {
+    Table,
+    UserId,
+    Name,
+    Password,
+    Role,
+    CreatedAt,
+    UpdatedAt,
+}
+
+#[derive(DeriveIden)]
+enum Drones {
+    Table,
+    DroneId,
+    Name,
+    Model,
+    Status,
+    Battery,
+    Activate,
+}
+
+#[derive(DeriveIden)]
+enum Missions {
+    Table,
+    MissionId,
+    UserId,
+    DroneId,
+    TargetLat,
+    TargetLng,
+    Status,
+    CreatedAt,
+    StartedAt,
+    CompletedAt,
+}
+
+#[derive(DeriveIden)]
+enum Events {
+    Table,
+    EventId,
+    MissionId,
+    EventType,
+    Message,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum Logs {
+    Table,
+    LogId,
+    LogType,
+    Message,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum Incidents {
+    Table,
+    IncidentId,
+    Title,
+    Description,
+    Lat,
+    Lng,
+    Radius,
+    Severity,
+    Status,
+    CreatedBy,
+    CreatedAt,
+    UpdatedAt,
+}
+
+const ROLES: [&str; 3] = ["superadmin", "admin", "user"];
+const STATUSES: [&str; 5] = ["idle", "working", "returning", "completed", "error"];
+const EVENT_TYPES: [&str; 6] = [
+    "takeoff",
+    "landing",
+    "battery_low",
+    "obstacle_detected",
+    "return",
+    "error",
+];
+const LOG_TYPES: [&str; 3] = ["INFO", "WARN", "ERROR"];
+const INCIDENT_STATUSES: [&str; 3] = ["open", "processing", "closed"];
+
+fn id(name: impl IntoIden) -> ColumnDef {
+    ColumnDef::new(name).string_len(32).not_null().primary_key().to_owned()
+}
+
+fn enumeration<const N: usize>(name: impl IntoIden, enum_name: &str, values: [&str; N]) -> ColumnDef {
+    ColumnDef::new(name)
+        .enumeration(
+            Alias::new(enum_name),
+            values.into_iter().map(Alias::new)
+        )
+        .not_null()
+        .to_owned()
+}
+
+fn created_at(name: impl IntoIden) -> ColumnDef {
+    ColumnDef::new(name).date_time().default(Expr::current_timestamp()).to_owned()
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager.create_table(
+            Table::create()
+                .table(Users::Table)
+                .if_not_exists()
+                .col(id(Users::UserId))
+                .col(ColumnDef::new(Users::Name).string_len(50).not_null())
+                .col(ColumnDef::new(Users::Password).string_len(255).not_null())
+                .col(enumeration(Users::Role, "role", ROLES))
+                .col(created_at(Users::CreatedAt))
+                .col(created_at(Users::UpdatedAt))
+                .to_owned()
+        ).await?;
+
+        manager.create_table(
+            Table::create()
+                .table(Drones::Table)
+                .if_not_exists()
+                .col(id(Drones::DroneId))
+                .col(ColumnDef::new(Drones::Name).string_len(50).not_null())
+                .col(ColumnDef::new(Drones::Model).string_len(50).not_null())
+                .col(enumeration(Drones::Status, "status", STATUSES).default("idle"))
+                .col(ColumnDef::new(Drones::Battery).tiny_unsigned().not_null())
+                .col(ColumnDef::new(Drones::Activate).boolean().not_null().default(true))
+                .to_owned()
+        ).await?;
+
+        manager.create_table(
+            Table::create()
+                .table(Missions::Table)
+                .if_not_exists()
+                .col(id(Missions::MissionId))
+                .col(ColumnDef::new(Missions::UserId).string_len(32).not_null())
+                .col(ColumnDef::new(Missions::DroneId).string_len(32).not_null())
+                .col(ColumnDef::new(Missions::TargetLat).decimal_len(9, 6))
+                .col(ColumnDef::new(Missions::TargetLng).decimal_len(9, 6))
+                .col(enumeration(Missions::Status, "status", STATUSES).default("idle"))
+                .col(created_at(Missions::CreatedAt).not_null())
+                .col(ColumnDef::new(Missions::StartedAt).date_time())
+                .col(ColumnDef::new(Missions::CompletedAt).date_time())
+                .foreign_key(
+                    ForeignKey::create()
+                        .from(Missions::Table, Missions::UserId)
+                        .to(Users::Table, Users::UserId)
+                )
+                .foreign_key(
+                    ForeignKey::create()
+                        .from(Missions::Table, Missions::DroneId)
+                        .to(Drones::Table, Drones::DroneId)
+                )
+                .to_owned()
+        ).await?;
+
+        manager.create_table(
+            Table::create()
+                .table(Events::Table)
+                .if_not_exists()
+                .col(id(Events::EventId))
+                .col(ColumnDef::new(Events::MissionId).string_len(32).not_null())
+                .col(enumeration(Events::EventType, "event_type", EVENT_TYPES))
+                .col(ColumnDef::new(Events::Message).text())
+                .col(created_at(Events::CreatedAt).not_null())
+                .foreign_key(
+                    ForeignKey::create()
+                        .from(Events::Table, Events::MissionId)
+                        .to(Missions::Table, Missions::MissionId)
+                )
+                .to_owned()
+        ).await?;
+
+        manager.create_table(
+            Table::create()
+                .table(Logs::Table)
+                .if_not_exists()
+                .col(
+                    ColumnDef::new(Logs::LogId)
+                        .integer()
+                        .not_null()
+                        .auto_increment()
+                        .primary_key()
+                )
+                .col(enumeration(Logs::LogType, "log_type", LOG_TYPES))
+                .col(ColumnDef::new(Logs::Message).text().not_null())
+                .col(created_at(Logs::CreatedAt))
+                .to_owned()
+        ).await?;
+
+        manager.create_table(
+            Table::create()
+                .table(Incidents::Table)
+                .if_not_exists()
+                .col(id(Incidents::IncidentId))
+                .col(ColumnDef::new(Incidents::Title).string_len(100).not_null())
+                .col(ColumnDef::new(Incidents::Description).text())
+                .col(ColumnDef::new(Incidents::Lat).decimal_len(9, 6).not_null())
+                .col(ColumnDef::new(Incidents::Lng).decimal_len(9, 6).not_null())
+                .col(ColumnDef::new(Incidents::Radius).float().default(20))
+                .col(ColumnDef::new(Incidents::Severity).tiny_integer().default(1))
+                .col(
+                    enumeration(Incidents::Status, "incident_status", INCIDENT_STATUSES).default(
+                        "open"
+                    )
+                )
+                .col(ColumnDef::new(Incidents::CreatedBy).string_len(32).not_null())
+                .col(created_at(Incidents::CreatedAt))
+                .col(created_at(Incidents::UpdatedAt))
+                .foreign_key(
+                    ForeignKey::create()
+                        .from(Incidents::Table, Incidents::CreatedBy)
+                        .to(Users::Table, Users::UserId)
+                )
+                .to_owned()
+        ).await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager.drop_table(Table::drop().table(Incidents::Table).to_owned()).await?;
+        manager.drop_table(Table::drop().table(Logs::Table).to_owned()).await?;
+        manager.drop_table(Table::drop().table(Events::Table).to_owned()).await?;
+        manager.drop_table(Table::drop().table(Missions::Table).to_owned()).await?;
+        manager.drop_table(Table::drop().table(Drones::Table).to_owned()).await?;
+        manager.drop_table(Table::drop().table(Users::Table).to_owned()).await?;
+
+        Ok(())
+    }
+}
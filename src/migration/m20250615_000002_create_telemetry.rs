@@ -0,0 +1,125 @@
+//! 无人机遥测数据表，并为无人机记录最近一次上报的位置
+
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[derive(DeriveIden)]
+enum Telemetry {
+    Table,
+    #[sea_orm(iden = "telemetry_id")]
+    Id,
+    DroneId,
+    MissionId,
+    Lat,
+    Lng,
+    Altitude,
+    Heading,
+    Speed,
+    Battery,
+    RecordedAt,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum Drones {
+    Table,
+    DroneId,
+    LastKnownLat,
+    LastKnownLng,
+    LastKnownAltitude,
+    LastSeenAt,
+}
+
+#[derive(DeriveIden)]
+enum Missions {
+    Table,
+    MissionId,
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager.create_table(
+            Table::create()
+                .table(Telemetry::Table)
+                .if_not_exists()
+                .col(
+                    ColumnDef::new(Telemetry::Id)
+                        .big_integer()
+                        .not_null()
+                        .auto_increment()
+                        .primary_key()
+                )
+                .col(ColumnDef::new(Telemetry::DroneId).string_len(32).not_null())
+                .col(ColumnDef::new(Telemetry::MissionId).string_len(32))
+                .col(ColumnDef::new(Telemetry::Lat).decimal_len(9, 6).not_null())
+                .col(ColumnDef::new(Telemetry::Lng).decimal_len(9, 6).not_null())
+                .col(ColumnDef::new(Telemetry::Altitude).float().not_null())
+                .col(ColumnDef::new(Telemetry::Heading).float())
+                .col(ColumnDef::new(Telemetry::Speed).float())
+                .col(ColumnDef::new(Telemetry::Battery).tiny_unsigned().not_null())
+                .col(ColumnDef::new(Telemetry::RecordedAt).date_time().not_null())
+                .col(
+                    ColumnDef::new(Telemetry::CreatedAt)
+                        .date_time()
+                        .not_null()
+                        .default(Expr::current_timestamp())
+                )
+                .foreign_key(
+                    ForeignKey::create()
+                        .from(Telemetry::Table, Telemetry::DroneId)
+                        .to(Drones::Table, Drones::DroneId)
+                )
+                .foreign_key(
+                    ForeignKey::create()
+                        .from(Telemetry::Table, Telemetry::MissionId)
+                        .to(Missions::Table, Missions::MissionId)
+                )
+                .to_owned()
+        ).await?;
+
+        manager.create_index(
+            Index::create()
+                .name("idx_telemetry_drone_recorded")
+                .table(Telemetry::Table)
+                .col(Telemetry::DroneId)
+                .col(Telemetry::RecordedAt)
+                .if_not_exists()
+                .to_owned()
+        ).await?;
+
+        // SQLite 每条 ALTER TABLE 只能添加一列
+        let columns = [
+            ColumnDef::new(Drones::LastKnownLat).decimal_len(9, 6).to_owned(),
+            ColumnDef::new(Drones::LastKnownLng).decimal_len(9, 6).to_owned(),
+            ColumnDef::new(Drones::LastKnownAltitude).float().to_owned(),
+            ColumnDef::new(Drones::LastSeenAt).date_time().to_owned(),
+        ];
+        for mut column in columns {
+            manager.alter_table(
+                Table::alter().table(Drones::Table).add_column(&mut column).to_owned()
+            ).await?;
+        }
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        for column in [
+            Drones::LastKnownLat,
+            Drones::LastKnownLng,
+            Drones::LastKnownAltitude,
+            Drones::LastSeenAt,
+        ] {
+            manager.alter_table(
+                Table::alter().table(Drones::Table).drop_column(column).to_owned()
+            ).await?;
+        }
+
+        manager.drop_table(Table::drop().table(Telemetry::Table).to_owned()).await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,34 @@
+//! 告警位置索引，用于附近、区域和覆盖范围查询的经纬度预筛选
+
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[derive(DeriveIden)]
+enum Incidents {
+    Table,
+    Lat,
+    Lng,
+}
+
+const INDEX_NAME: &str = "idx_incidents_location";
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager.create_index(
+            Index::create()
+                .name(INDEX_NAME)
+                .table(Incidents::Table)
+                .col(Incidents::Lat)
+                .col(Incidents::Lng)
+                .if_not_exists()
+                .to_owned()
+        ).await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager.drop_index(Index::drop().name(INDEX_NAME).table(Incidents::Table).to_owned()).await
+    }
+}
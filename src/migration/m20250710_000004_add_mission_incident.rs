@@ -0,0 +1,47 @@
+//! 任务关联触发它的告警，手动创建的任务为空
+
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[derive(DeriveIden)]
+enum Missions {
+    Table,
+    IncidentId,
+}
+
+const INDEX_NAME: &str = "idx_missions_incident";
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager.alter_table(
+            Table::alter()
+                .table(Missions::Table)
+                .add_column(ColumnDef::new(Missions::IncidentId).string_len(32))
+                .to_owned()
+        ).await?;
+
+        manager.create_index(
+            Index::create()
+                .name(INDEX_NAME)
+                .table(Missions::Table)
+                .col(Missions::IncidentId)
+                .if_not_exists()
+                .to_owned()
+        ).await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager.drop_index(Index::drop().name(INDEX_NAME).table(Missions::Table).to_owned()).await?;
+
+        manager.alter_table(
+            Table::alter().table(Missions::Table).drop_column(Missions::IncidentId).to_owned()
+        ).await?;
+
+        Ok(())
+    }
+}
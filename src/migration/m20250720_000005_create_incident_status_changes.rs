@@ -0,0 +1,83 @@
+//! 告警状态变更记录，用于告警时间线
+
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[derive(DeriveIden)]
+enum IncidentStatusChanges {
+    Table,
+    #[sea_orm(iden = "change_id")]
+    Id,
+    IncidentId,
+    FromStatus,
+    ToStatus,
+    ChangedBy,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum Incidents {
+    Table,
+    IncidentId,
+}
+
+const INCIDENT_STATUSES: [&str; 3] = ["open", "processing", "closed"];
+
+fn status(name: IncidentStatusChanges) -> ColumnDef {
+    ColumnDef::new(name)
+        .enumeration(Alias::new("incident_status"), INCIDENT_STATUSES.map(Alias::new))
+        .not_null()
+        .to_owned()
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager.create_table(
+            Table::create()
+                .table(IncidentStatusChanges::Table)
+                .if_not_exists()
+                .col(
+                    ColumnDef::new(IncidentStatusChanges::Id)
+                        .big_integer()
+                        .not_null()
+                        .auto_increment()
+                        .primary_key()
+                )
+                .col(ColumnDef::new(IncidentStatusChanges::IncidentId).string_len(32).not_null())
+                .col(status(IncidentStatusChanges::FromStatus))
+                .col(status(IncidentStatusChanges::ToStatus))
+                .col(ColumnDef::new(IncidentStatusChanges::ChangedBy).string_len(32))
+                .col(
+                    ColumnDef::new(IncidentStatusChanges::CreatedAt)
+                        .date_time()
+                        .not_null()
+                        .default(Expr::current_timestamp())
+                )
+                .foreign_key(
+                    ForeignKey::create()
+                        .from(IncidentStatusChanges::Table, IncidentStatusChanges::IncidentId)
+                        .to(Incidents::Table, Incidents::IncidentId)
+                        .on_delete(ForeignKeyAction::Cascade)
+                )
+                .to_owned()
+        ).await?;
+
+        manager.create_index(
+            Index::create()
+                .name("idx_incident_status_changes_incident")
+                .table(IncidentStatusChanges::Table)
+                .col(IncidentStatusChanges::IncidentId)
+                .if_not_exists()
+                .to_owned()
+        ).await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager.drop_table(Table::drop().table(IncidentStatusChanges::Table).to_owned()).await
+    }
+}
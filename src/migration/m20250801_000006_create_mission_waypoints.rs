@@ -0,0 +1,94 @@
+//! 任务航点，按 seq 顺序组成飞行航线
+
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[derive(DeriveIden)]
+enum MissionWaypoints {
+    Table,
+    #[sea_orm(iden = "waypoint_id")]
+    Id,
+    MissionId,
+    Seq,
+    Lat,
+    Lng,
+    Altitude,
+    LoiterSeconds,
+    Action,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum Missions {
+    Table,
+    MissionId,
+}
+
+const WAYPOINT_ACTIONS: [&str; 5] = ["fly_through", "hover", "photo", "video", "land"];
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager.create_table(
+            Table::create()
+                .table(MissionWaypoints::Table)
+                .if_not_exists()
+                .col(
+                    ColumnDef::new(MissionWaypoints::Id)
+                        .big_integer()
+                        .not_null()
+                        .auto_increment()
+                        .primary_key()
+                )
+                .col(ColumnDef::new(MissionWaypoints::MissionId).string_len(32).not_null())
+                .col(ColumnDef::new(MissionWaypoints::Seq).integer().not_null())
+                .col(ColumnDef::new(MissionWaypoints::Lat).decimal_len(9, 6).not_null())
+                .col(ColumnDef::new(MissionWaypoints::Lng).decimal_len(9, 6).not_null())
+                .col(ColumnDef::new(MissionWaypoints::Altitude).float().not_null())
+                .col(
+                    ColumnDef::new(MissionWaypoints::LoiterSeconds)
+                        .integer()
+                        .not_null()
+                        .default(0)
+                )
+                .col(
+                    ColumnDef::new(MissionWaypoints::Action)
+                        .enumeration(Alias::new("waypoint_action"), WAYPOINT_ACTIONS.map(Alias::new))
+                        .not_null()
+                        .default("fly_through")
+                )
+                .col(
+                    ColumnDef::new(MissionWaypoints::CreatedAt)
+                        .date_time()
+                        .not_null()
+                        .default(Expr::current_timestamp())
+                )
+                .foreign_key(
+                    ForeignKey::create()
+                        .from(MissionWaypoints::Table, MissionWaypoints::MissionId)
+                        .to(Missions::Table, Missions::MissionId)
+                        .on_delete(ForeignKeyAction::Cascade)
+                )
+                .to_owned()
+        ).await?;
+
+        // 重排航点时会临时出现重复序号，因此不加唯一约束
+        manager.create_index(
+            Index::create()
+                .name("idx_mission_waypoints_mission_seq")
+                .table(MissionWaypoints::Table)
+                .col(MissionWaypoints::MissionId)
+                .col(MissionWaypoints::Seq)
+                .if_not_exists()
+                .to_owned()
+        ).await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager.drop_table(Table::drop().table(MissionWaypoints::Table).to_owned()).await
+    }
+}
@@ -0,0 +1,81 @@
+//! 地理围栏：圆形或多边形区域，分为允许飞行区和禁飞区，可设置生效时间段
+
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[derive(DeriveIden)]
+enum Geofences {
+    Table,
+    GeofenceId,
+    Name,
+    Shape,
+    Kind,
+    CenterLat,
+    CenterLng,
+    Radius,
+    Vertices,
+    ActiveFrom,
+    ActiveUntil,
+    CreatedBy,
+    CreatedAt,
+    UpdatedAt,
+}
+
+const SHAPES: [&str; 2] = ["circle", "polygon"];
+const KINDS: [&str; 2] = ["inclusion", "exclusion"];
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager.create_table(
+            Table::create()
+                .table(Geofences::Table)
+                .if_not_exists()
+                .col(ColumnDef::new(Geofences::GeofenceId).string_len(32).not_null().primary_key())
+                .col(ColumnDef::new(Geofences::Name).string_len(100).not_null())
+                .col(
+                    ColumnDef::new(Geofences::Shape)
+                        .enumeration(Alias::new("geofence_shape"), SHAPES.map(Alias::new))
+                        .not_null()
+                )
+                .col(
+                    ColumnDef::new(Geofences::Kind)
+                        .enumeration(Alias::new("geofence_kind"), KINDS.map(Alias::new))
+                        .not_null()
+                )
+                .col(ColumnDef::new(Geofences::CenterLat).decimal_len(9, 6))
+                .col(ColumnDef::new(Geofences::CenterLng).decimal_len(9, 6))
+                .col(ColumnDef::new(Geofences::Radius).float())
+                .col(ColumnDef::new(Geofences::Vertices).json())
+                .col(ColumnDef::new(Geofences::ActiveFrom).date_time())
+                .col(ColumnDef::new(Geofences::ActiveUntil).date_time())
+                .col(ColumnDef::new(Geofences::CreatedBy).string_len(32).not_null())
+                .col(
+                    ColumnDef::new(Geofences::CreatedAt)
+                        .date_time()
+                        .not_null()
+                        .default(Expr::current_timestamp())
+                )
+                .col(ColumnDef::new(Geofences::UpdatedAt).date_time())
+                .to_owned()
+        ).await?;
+
+        // 预检时按类型查询生效中的围栏
+        manager.create_index(
+            Index::create()
+                .name("idx_geofences_kind")
+                .table(Geofences::Table)
+                .col(Geofences::Kind)
+                .if_not_exists()
+                .to_owned()
+        ).await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager.drop_table(Table::drop().table(Geofences::Table).to_owned()).await
+    }
+}
@@ -0,0 +1,128 @@
+//! 任务模板按 cron 表达式定时生成任务，生成的任务关联来源模板
+
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[derive(DeriveIden)]
+enum MissionTemplates {
+    Table,
+    TemplateId,
+    Name,
+    UserId,
+    DroneId,
+    DroneModel,
+    TargetLat,
+    TargetLng,
+    Waypoints,
+    Cron,
+    Paused,
+    LastRunAt,
+    NextRunAt,
+    CreatedAt,
+    UpdatedAt,
+}
+
+#[derive(DeriveIden)]
+enum Missions {
+    Table,
+    TemplateId,
+}
+
+#[derive(DeriveIden)]
+enum Users {
+    Table,
+    UserId,
+}
+
+#[derive(DeriveIden)]
+enum Drones {
+    Table,
+    DroneId,
+}
+
+const MISSION_INDEX: &str = "idx_missions_template";
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager.create_table(
+            Table::create()
+                .table(MissionTemplates::Table)
+                .if_not_exists()
+                .col(ColumnDef::new(MissionTemplates::TemplateId).string_len(32).not_null().primary_key())
+                .col(ColumnDef::new(MissionTemplates::Name).string_len(100).not_null())
+                .col(ColumnDef::new(MissionTemplates::UserId).string_len(32).not_null())
+                .col(ColumnDef::new(MissionTemplates::DroneId).string_len(32))
+                .col(ColumnDef::new(MissionTemplates::DroneModel).string_len(50))
+                .col(ColumnDef::new(MissionTemplates::TargetLat).decimal_len(9, 6).not_null())
+                .col(ColumnDef::new(MissionTemplates::TargetLng).decimal_len(9, 6).not_null())
+                .col(ColumnDef::new(MissionTemplates::Waypoints).json())
+                .col(ColumnDef::new(MissionTemplates::Cron).string_len(100).not_null())
+                .col(ColumnDef::new(MissionTemplates::Paused).boolean().not_null().default(false))
+                .col(ColumnDef::new(MissionTemplates::LastRunAt).date_time())
+                .col(ColumnDef::new(MissionTemplates::NextRunAt).date_time())
+                .col(
+                    ColumnDef::new(MissionTemplates::CreatedAt)
+                        .date_time()
+                        .not_null()
+                        .default(Expr::current_timestamp())
+                )
+                .col(ColumnDef::new(MissionTemplates::UpdatedAt).date_time())
+                .foreign_key(
+                    ForeignKey::create()
+                        .from(MissionTemplates::Table, MissionTemplates::UserId)
+                        .to(Users::Table, Users::UserId)
+                        .on_delete(ForeignKeyAction::Cascade)
+                )
+                .foreign_key(
+                    ForeignKey::create()
+                        .from(MissionTemplates::Table, MissionTemplates::DroneId)
+                        .to(Drones::Table, Drones::DroneId)
+                        .on_delete(ForeignKeyAction::SetNull)
+                )
+                .to_owned()
+        ).await?;
+
+        // 调度器按下次执行时间查找到期的模板
+        manager.create_index(
+            Index::create()
+                .name("idx_mission_templates_next_run")
+                .table(MissionTemplates::Table)
+                .col(MissionTemplates::Paused)
+                .col(MissionTemplates::NextRunAt)
+                .if_not_exists()
+                .to_owned()
+        ).await?;
+
+        // SQLite 不支持为已有表添加外键，模板删除时由接口解除关联
+        manager.alter_table(
+            Table::alter()
+                .table(Missions::Table)
+                .add_column(ColumnDef::new(Missions::TemplateId).string_len(32))
+                .to_owned()
+        ).await?;
+
+        manager.create_index(
+            Index::create()
+                .name(MISSION_INDEX)
+                .table(Missions::Table)
+                .col(Missions::TemplateId)
+                .if_not_exists()
+                .to_owned()
+        ).await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager.drop_index(Index::drop().name(MISSION_INDEX).table(Missions::Table).to_owned()).await?;
+
+        manager.alter_table(
+            Table::alter().table(Missions::Table).drop_column(Missions::TemplateId).to_owned()
+        ).await?;
+
+        manager.drop_table(Table::drop().table(MissionTemplates::Table).to_owned()).await
+    }
+}
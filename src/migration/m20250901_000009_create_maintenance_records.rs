@@ -0,0 +1,114 @@
+//! 无人机维护记录；无人机状态增加 maintenance，与 `scripts/init.sql` 一致
+
+use sea_orm_migration::prelude::*;
+use sea_orm_migration::sea_orm::DbBackend;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[derive(DeriveIden)]
+enum MaintenanceRecords {
+    Table,
+    #[sea_orm(iden = "record_id")]
+    Id,
+    DroneId,
+    MaintenanceType,
+    Technician,
+    Notes,
+    Parts,
+    StartedAt,
+    EndedAt,
+    CreatedBy,
+}
+
+#[derive(DeriveIden)]
+enum Drones {
+    Table,
+    DroneId,
+    Status,
+}
+
+const STATUSES: [&str; 5] = ["idle", "working", "returning", "completed", "error"];
+const DRONE_STATUSES: [&str; 6] = ["idle", "working", "returning", "completed", "error", "maintenance"];
+const MAINTENANCE_TYPES: [&str; 5] = ["inspection", "repair", "battery", "firmware", "other"];
+
+fn drone_status<const N: usize>(values: [&str; N]) -> ColumnDef {
+    ColumnDef::new(Drones::Status)
+        .enumeration(Alias::new("status"), values.map(Alias::new))
+        .not_null()
+        .default("idle")
+        .to_owned()
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // SQLite 的枚举列为文本，只有 MySQL 需要扩充枚举值
+        if manager.get_database_backend() == DbBackend::MySql {
+            manager.alter_table(
+                Table::alter().table(Drones::Table).modify_column(drone_status(DRONE_STATUSES)).to_owned()
+            ).await?;
+        }
+
+        manager.create_table(
+            Table::create()
+                .table(MaintenanceRecords::Table)
+                .if_not_exists()
+                .col(
+                    ColumnDef::new(MaintenanceRecords::Id)
+                        .big_integer()
+                        .not_null()
+                        .auto_increment()
+                        .primary_key()
+                )
+                .col(ColumnDef::new(MaintenanceRecords::DroneId).string_len(32).not_null())
+                .col(
+                    ColumnDef::new(MaintenanceRecords::MaintenanceType)
+                        .enumeration(Alias::new("maintenance_type"), MAINTENANCE_TYPES.map(Alias::new))
+                        .not_null()
+                )
+                .col(ColumnDef::new(MaintenanceRecords::Technician).string_len(50).not_null())
+                .col(ColumnDef::new(MaintenanceRecords::Notes).text())
+                .col(ColumnDef::new(MaintenanceRecords::Parts).json())
+                .col(
+                    ColumnDef::new(MaintenanceRecords::StartedAt)
+                        .date_time()
+                        .not_null()
+                        .default(Expr::current_timestamp())
+                )
+                .col(ColumnDef::new(MaintenanceRecords::EndedAt).date_time())
+                .col(ColumnDef::new(MaintenanceRecords::CreatedBy).string_len(32))
+                .foreign_key(
+                    ForeignKey::create()
+                        .from(MaintenanceRecords::Table, MaintenanceRecords::DroneId)
+                        .to(Drones::Table, Drones::DroneId)
+                        .on_delete(ForeignKeyAction::Cascade)
+                )
+                .to_owned()
+        ).await?;
+
+        manager.create_index(
+            Index::create()
+                .name("idx_maintenance_records_drone")
+                .table(MaintenanceRecords::Table)
+                .col(MaintenanceRecords::DroneId)
+                .col(MaintenanceRecords::StartedAt)
+                .if_not_exists()
+                .to_owned()
+        ).await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager.drop_table(Table::drop().table(MaintenanceRecords::Table).to_owned()).await?;
+
+        if manager.get_database_backend() == DbBackend::MySql {
+            manager.alter_table(
+                Table::alter().table(Drones::Table).modify_column(drone_status(STATUSES)).to_owned()
+            ).await?;
+        }
+
+        Ok(())
+    }
+}
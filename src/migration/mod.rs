@@ -0,0 +1,30 @@
+pub use sea_orm_migration::prelude::*;
+
+mod m20250601_000001_create_tables;
+mod m20250615_000002_create_telemetry;
+mod m20250701_000003_index_incident_location;
+mod m20250710_000004_add_mission_incident;
+mod m20250720_000005_create_incident_status_changes;
+mod m20250801_000006_create_mission_waypoints;
+mod m20250810_000007_create_geofences;
+mod m20250820_000008_create_mission_templates;
+mod m20250901_000009_create_maintenance_records;
+
+pub struct Migrator;
+
+#[async_trait::async_trait]
+impl MigratorTrait for Migrator {
+    fn migrations() -> Vec<Box<dyn MigrationTrait>> {
+        vec![
+            Box::new(m20250601_000001_create_tables::Migration),
+            Box::new(m20250615_000002_create_telemetry::Migration),
+            Box::new(m20250701_000003_index_incident_location::Migration),
+            Box::new(m20250710_000004_add_mission_incident::Migration),
+            Box::new(m20250720_000005_create_incident_status_changes::Migration),
+            Box::new(m20250801_000006_create_mission_waypoints::Migration),
+            Box::new(m20250810_000007_create_geofences::Migration),
+            Box::new(m20250820_000008_create_mission_templates::Migration),
+            Box::new(m20250901_000009_create_maintenance_records::Migration)
+        ]
+    }
+}
@@ -0,0 +1,32 @@
+use std::time::Duration;
+
+use sea_orm::DatabaseConnection;
+use tokio::time::MissedTickBehavior;
+
+use crate::api::template;
+use crate::common::config::SchedulerConfig;
+use crate::hub::Hub;
+
+// 后台定时检查到期的任务模板并生成任务
+pub fn spawn(db: DatabaseConnection, hub: Hub, config: &SchedulerConfig) {
+    if !config.enabled {
+        tracing::info!("任务调度器未开启");
+        return;
+    }
+
+    let period = Duration::from_secs(config.interval);
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(period);
+        // 单次执行耗时过长时不补跑错过的检查
+        interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+        loop {
+            interval.tick().await;
+            match template::run_due_templates(&db, &hub).await {
+                Ok(0) => {}
+                Ok(created) => tracing::info!("按任务模板生成了 {} 个任务", created),
+                Err(e) => tracing::error!("执行定时任务失败: {}", e),
+            }
+        }
+    });
+}